@@ -9,7 +9,7 @@ lazy_static! {
 }
 
 fn main() {
-	let lines = stdin().lines().flatten().collect::<Vec<_>>();
+	let lines = stdin().lines().map_while(Result::ok).collect::<Vec<_>>();
 
 	println!("Part 1: {}", solve(&NUMBER_PATTERN, &lines));
 	println!("Part 2: {}", solve(&NUMBER_AND_DIGIT_PATTERN, &lines));
@@ -1,15 +1,17 @@
 use std::io::stdin;
 
+use aoc2023::parse::{self, Scanner};
+
 fn main() {
-	let lines = stdin().lines().flatten();
+	let lines = stdin().lines().map_while(Result::ok);
 	let minimum_cubes_per_game: Vec<[usize; 3]> = lines
 		.map(|line| {
-			let games = line.split_once(": ").unwrap().1;
-			let draws = games.split("; ").flat_map(|draws| draws.split(", "));
-			let cubes = draws.map(|draw| {
-				let (amount, color) = draw.split_once(' ').unwrap();
-				let amount: usize = amount.parse().unwrap();
-				let color: usize = parse_color(color).unwrap();
+			let (_, draws) = parse::key_value(&line).unwrap();
+			let cubes = draws.split([';', ',']).map(|draw| {
+				let mut scanner = Scanner::new(draw.trim());
+				let amount: usize = scanner.number().unwrap();
+				scanner.expect(" ").unwrap();
+				let color = parse_color(scanner.word().unwrap()).unwrap();
 				(color, amount)
 			});
 			cubes.fold([0; 3], |mut acc, (color, amount)| {
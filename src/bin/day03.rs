@@ -53,7 +53,7 @@ impl Symbol {
 }
 
 fn main() {
-	let lines = stdin().lines().flatten();
+	let lines = stdin().lines().map_while(Result::ok);
 	let input = lines.collect::<Vec<_>>();
 
 	let numbers = input
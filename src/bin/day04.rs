@@ -1,17 +1,16 @@
 use std::io::stdin;
 
+use aoc2023::parse;
+
 fn main() {
-	let lines = stdin().lines().flatten();
+	let lines = stdin().lines().map_while(Result::ok);
 	let scratchcard_scores = lines
 		.map(|line| {
-			let numbers = line.split_once(": ").unwrap().1;
+			let (_, numbers) = parse::key_value(&line).unwrap();
 			let (winning_numbers, my_numbers) = numbers.split_once(" | ").unwrap();
 
-			let collect_to_bitset = |xs: &str| {
-				xs.split_whitespace()
-					.map(|n| n.parse::<u8>().unwrap())
-					.fold(0u128, |acc, it| acc | 1 << it)
-			};
+			let collect_to_bitset =
+				|xs: &str| parse::integers::<u8>(xs).fold(0u128, |acc, it| acc | 1 << it.unwrap());
 
 			let winning_numbers = collect_to_bitset(winning_numbers);
 			let my_numbers = collect_to_bitset(my_numbers);
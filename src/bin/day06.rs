@@ -3,7 +3,7 @@ use std::{
 	ops::RangeInclusive,
 };
 
-use itertools::Itertools;
+use aoc2023::{math, parse, range};
 
 fn main() {
 	let input = {
@@ -12,46 +12,32 @@ fn main() {
 		buf
 	};
 
-	let lines = input
-		.lines()
-		.map(|line| line.split_whitespace().skip(1).collect::<Vec<_>>())
-		.collect::<Vec<_>>();
+	let lines = input.lines().collect::<Vec<_>>();
 
-	fn parse<'a>(numbers: &'a [&str]) -> impl Iterator<Item = usize> + 'a {
-		numbers
-			.iter()
-			.map(|number| number.parse::<usize>().unwrap())
-	}
-	let times = parse(&lines[0]);
-	let distances = parse(&lines[1]);
+	let times = parse::integers::<usize>(lines[0]).map(Result::unwrap);
+	let distances = parse::integers::<usize>(lines[1]).map(Result::unwrap);
 
 	let races = times.zip(distances);
 	let part1: usize = races
-		.map(|(time, distance)| hold_times(time, distance).count())
+		.map(|(time, distance)| range::len(&hold_times(time, distance)))
 		.product::<usize>();
 	println!("Part 1: {part1}");
 
-	let parse = |numbers: &[&str]| numbers.iter().join("").parse().unwrap();
-	let time = parse(&lines[0]);
-	let distance = parse(&lines[1]);
-	let part2 = hold_times(time, distance).count();
+	// The spaces between the digits are just bad kerning
+	let [time] = parse::ints(&lines[0].replace(' ', "")).unwrap();
+	let [distance] = parse::ints(&lines[1].replace(' ', "")).unwrap();
+	let part2 = range::len(&hold_times(time, distance));
 	println!("Part 2: {part2}");
 }
 
 fn hold_times(race_time: usize, target: usize) -> RangeInclusive<usize> {
-	let mut hold_times = (1..)
-		.map(move |hold_time| (hold_time, distance(hold_time, race_time)))
-		.skip_while(move |(_, distance)| *distance <= target)
-		.take_while(move |(_, distance)| *distance > target)
-		.map(|(hold_time, _)| hold_time);
-
-	let start = hold_times.next().unwrap();
-	let end = hold_times.last().unwrap();
-	start..=end
-}
+	/* The distance is `hold_time * (race_time - hold_time)`, which needs to be larger than the
+	 * target. Rewritten, this is the range where `hold_time² - race_time * hold_time + target` is
+	 * below zero, which lies between its roots. */
+	let (low, high) = math::quadratic_root_bounds(1, -(race_time as i64), target as i64)
+		.expect("Target to be reachable");
 
-fn distance(hold_time: usize, race_time: usize) -> usize {
-	hold_time * (race_time - hold_time)
+	(low as usize + 1)..=(high as usize - 1)
 }
 
 #[cfg(test)]
@@ -64,14 +50,4 @@ mod tests {
 		assert_eq!(hold_times(15, 40), 4..=11);
 		assert_eq!(hold_times(30, 200), 11..=19);
 	}
-
-	#[test]
-	fn test_distance() {
-		assert_eq!(distance(1, 7), 6);
-		assert_eq!(distance(2, 7), 10);
-		assert_eq!(distance(3, 7), 12);
-		assert_eq!(distance(4, 7), 12);
-		assert_eq!(distance(5, 7), 10);
-		assert_eq!(distance(6, 7), 6);
-	}
 }
@@ -3,7 +3,7 @@ use std::io::stdin;
 use itertools::Itertools;
 
 fn main() {
-	let cards = parse_input(stdin().lines().flatten());
+	let cards = parse_input(stdin().lines().map_while(Result::ok));
 
 	let calculate_score = |find_hand: fn(deck: &cards::Deck) -> cards::hand::Hand| {
 		cards
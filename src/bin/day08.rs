@@ -1,11 +1,21 @@
-use std::{collections::HashMap, io::stdin};
+use std::io::{stdin, Read};
+
+use aoc2023::{
+	interner::Interner,
+	math,
+	parse::{self, Scanner},
+};
+use itertools::Itertools;
 
 fn main() {
-	let mut lines = stdin().lines().flatten();
+	let input = {
+		let mut buf = String::new();
+		stdin().read_to_string(&mut buf).unwrap();
+		buf
+	};
+	let (instructions, map_lines) = parse::blocks(&input).collect_tuple().unwrap();
 
-	let instructions = lines
-		.next()
-		.unwrap()
+	let instructions = instructions
 		.bytes()
 		.map(|lr| match lr {
 			b'L' => 0,
@@ -14,60 +24,60 @@ fn main() {
 		})
 		.collect::<Vec<_>>();
 
-	let map_lines = lines.skip(1).collect::<Vec<_>>();
-
-	let positions = map_lines.iter().map(|line| &line[0..3]).collect::<Vec<_>>();
+	let map_lines = map_lines
+		.lines()
+		.map(|line| parse_map_line(line).unwrap())
+		.collect::<Vec<_>>();
 
-	let position_indices = positions
+	/* Intern the positions in the order of the lines, so the ids can directly be used as indices
+	 * into the map. */
+	let positions = map_lines
 		.iter()
-		.copied()
-		.zip(0..)
-		.collect::<HashMap<_, _>>();
+		.map(|(position, _, _)| *position)
+		.collect::<Interner>();
 
 	let map = map_lines
 		.iter()
-		.map(|line| {
-			let left = &line[7..10];
-			let right = &line[12..15];
-
-			[position_indices[left], position_indices[right]]
-		})
+		.map(|(_, left, right)| [left, right].map(|p| positions.get(p).unwrap()))
 		.collect::<Vec<_>>();
 
-	let find_travel_time = |start_position: usize, is_end_position: fn(&str) -> bool| {
+	let find_travel_time = |start_position: u32, is_end_position: fn(&str) -> bool| {
 		instructions
 			.iter()
 			.cycle()
 			.scan(start_position, |position, instruction| {
-				*position = map[*position][*instruction];
+				*position = map[*position as usize][*instruction];
 				Some(*position)
 			})
-			.take_while(|position| !is_end_position(positions[*position]))
+			.take_while(|position| !is_end_position(&positions[*position]))
 			.count() + 1 // Count doesn't include the end position
 	};
 
 	println!("Part 1: {}", {
 		let predicate = |position: &str| position == "ZZZ";
-		find_travel_time(position_indices["AAA"], predicate)
+		find_travel_time(positions.get("AAA").unwrap(), predicate)
 	});
 
-	let start_positions = position_indices
+	let start_positions = positions
 		.iter()
-		.filter_map(|(key, value)| key.ends_with('A').then_some(*value));
+		.filter_map(|(id, position)| position.ends_with('A').then_some(id));
 	let travel_times = start_positions.map(|position| {
 		let predicate = |position: &str| position.ends_with('Z');
 		find_travel_time(position, predicate)
 	});
 
-	println!(
-		"Part 2: {}",
-		travel_times.reduce(lcm).unwrap()
-	);
+	println!("Part 2: {}", math::lcm_all(travel_times));
 }
 
-fn lcm(x: usize, y: usize) -> usize {
-	let min = x.min(y);
-	let max = x.max(y);
-
-	(max..).step_by(max).find(|x| x % min == 0).unwrap()
+/// Parses a line like `AAA = (BBB, CCC)` into its position, and the positions to the left and right.
+fn parse_map_line(line: &str) -> anyhow::Result<(&str, &str, &str)> {
+	let mut scanner = Scanner::new(line);
+	let position = scanner.word()?;
+	scanner.expect(" = (")?;
+	let left = scanner.word()?;
+	scanner.expect(", ")?;
+	let right = scanner.word()?;
+	scanner.expect(")")?;
+	scanner.finish()?;
+	Ok((position, left, right))
 }
@@ -1,6 +1,7 @@
 use std::io::stdin;
 
 use aoc2023::{
+	geometry,
 	grid2d::{Grid2D, Grid2DGet as _},
 	vector2d::Vector2D,
 };
@@ -15,7 +16,7 @@ const DIRECTIONS: [Vector2D; 4] = [UP, RIGHT, DOWN, LEFT];
 fn main() {
 	let mut input: Grid2D<u8> = stdin()
 		.lines()
-		.flatten()
+		.map_while(Result::ok)
 		.map(|x| x.bytes().collect::<Vec<_>>())
 		.collect();
 
@@ -28,53 +29,16 @@ fn main() {
 
 	let main_loop = find_main_loop(&input, start.into());
 
-	println!(
-		"Part 1: {}",
-		main_loop.enumerate().filter(|(_, v)| **v).count() / 2
-	);
-
-	let mut enclosed_positions = 0;
-
-	// Walk over all positions that aren't part of the main loop
-	for ((x, y), _) in main_loop.enumerate().filter(|(_, is_pipe)| !**is_pipe) {
-		let mut pipes_hit = 0;
-
-		// Cast a ray to the right from the current position
-		let ray = x..main_loop.width();
-		let mut pipes = ray
-			// Only include pipes on the ray that are part of the main loop
-			.filter(|&x| main_loop[(x, y)])
-			// Transform to the actual pipes
-			.map(|x| input[(x, y)])
-			// Ignore the horizontal pipes
-			.filter(|c| *c != b'-');
-
-		while let Some(symbol) = pipes.next() {
-			if symbol == b'|' {
-				pipes_hit += 1;
-				continue;
-			}
-
-			let next_symbol = pipes.next().expect("Matching corner");
-
-			match (symbol, next_symbol) {
-				// The pair of these corners effectively acts as a single wall
-				(b'F', b'J') | (b'L', b'7') => pipes_hit += 1,
-
-				// These corners cancel each other out
-				(b'F', b'7') | (b'L', b'J') => {}
-
-				_ => panic!("Mismatched corner pair"),
-			}
-		}
-
-		let is_enclosed = pipes_hit % 2 != 0;
-		if is_enclosed {
-			enclosed_positions += 1;
-		}
+	// Pass `--show-loop` to draw the loop and the enclosed positions on stderr
+	if std::env::args().any(|arg| arg == "--show-loop") {
+		eprintln!("{}", render_loop(&input, &main_loop));
 	}
 
-	println!("Part 2: {enclosed_positions}");
+	println!("Part 1: {}", main_loop.len() / 2);
+
+	/* The tiles of the loop form a polygon, so the enclosed positions are the lattice points
+	 * inside of it. */
+	println!("Part 2: {}", geometry::interior_points(&main_loop));
 }
 
 fn reconstruct_start(input: &Grid2D<u8>, start: Vector2D) -> u8 {
@@ -107,35 +71,85 @@ fn reconstruct_start(input: &Grid2D<u8>, start: Vector2D) -> u8 {
 	}
 }
 
-fn find_main_loop(input: &Grid2D<u8>, start: Vector2D) -> Grid2D<bool> {
-	let mut main_loop: Grid2D<bool> = Grid2D::with_size(input.width(), input.height());
-	main_loop[start] = true;
+/// The positions of the main loop, in the order in which they're visited when following the
+/// pipes from the start.
+fn find_main_loop(input: &Grid2D<u8>, start: Vector2D) -> Vec<Vector2D> {
+	let mut main_loop = vec![start];
 
 	let mut position = start;
 
-	// Pick the first direction we can move to
+	/* Pick the first direction we can move to: the direction in which we'd leave the start, when
+	 * entering it from any direction */
 	let mut direction = DIRECTIONS
 		.into_iter()
-		.find(|&direction| {
-			let symbol = input[start];
-			adjust_course(direction, symbol).is_some()
-		})
+		.find_map(|direction| adjust_course(direction, input[start]))
 		.expect("Start to be connected to a pipe");
 
 	loop {
 		position = position + direction;
 
-		if main_loop[position] {
+		if position == start {
 			break;
 		}
 
-		main_loop[position] = true;
+		main_loop.push(position);
 		direction = adjust_course(direction, input[position]).unwrap();
 	}
 
 	main_loop
 }
 
+/// Draws the main loop with box-drawing characters, with the (reconstructed) start in heavy lines.
+/// The other positions are marked with `I` when they're enclosed by the loop, or `O` otherwise.
+fn render_loop(input: &Grid2D<u8>, main_loop: &[Vector2D]) -> String {
+	let mut is_loop: Grid2D<bool> = Grid2D::with_size(input.width(), input.height());
+	for &position in main_loop {
+		is_loop[position] = true;
+	}
+
+	let mut output = String::new();
+	for y in 0..input.height() {
+		/* Walking along the row, the position is inside of the loop after crossing it an odd
+		 * amount of times. Only counting the pipes that connect upwards handles the corners. */
+		let mut inside = false;
+
+		for x in 0..input.width() {
+			let symbol = input[(x, y)];
+
+			output.push(if !is_loop[(x, y)] {
+				if inside {
+					'I'
+				} else {
+					'O'
+				}
+			} else {
+				if matches!(symbol, b'|' | b'L' | b'J') {
+					inside = !inside;
+				}
+
+				let [light, heavy] = match symbol {
+					b'|' => ['│', '┃'],
+					b'-' => ['─', '━'],
+					b'L' => ['└', '┗'],
+					b'J' => ['┘', '┛'],
+					b'7' => ['┐', '┓'],
+					b'F' => ['┌', '┏'],
+					_ => panic!("Unexpected symbol '{}' in the loop", symbol as char),
+				};
+				if Vector2D::from((x, y)) == main_loop[0] {
+					heavy
+				} else {
+					light
+				}
+			});
+		}
+
+		output.push('\n');
+	}
+
+	output
+}
+
 fn adjust_course(direction: Vector2D, pipe: u8) -> Option<Vector2D> {
 	Some(match (pipe, direction) {
 		(b'|', UP) => UP,
@@ -157,3 +171,76 @@ fn adjust_course(direction: Vector2D, pipe: u8) -> Option<Vector2D> {
 		_ => None?,
 	})
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn parse(input: &str) -> (Grid2D<u8>, Vec<Vector2D>) {
+		let mut grid = input
+			.lines()
+			.map(|line| line.bytes().collect::<Vec<_>>())
+			.collect::<Grid2D<_>>();
+		let start = grid
+			.enumerate()
+			.find_map(|(index, symbol)| (*symbol == b'S').then_some(index))
+			.unwrap();
+		grid[start] = reconstruct_start(&grid, start.into());
+
+		let main_loop = find_main_loop(&grid, start.into());
+		(grid, main_loop)
+	}
+
+	#[test]
+	fn test_farthest_position() {
+		assert_eq!(parse(".....\n.S-7.\n.|.|.\n.L-J.\n.....").1.len() / 2, 4);
+		assert_eq!(parse("..F7.\n.FJ|.\nSJ.L7\n|F--J\nLJ...").1.len() / 2, 8);
+	}
+
+	#[test]
+	fn test_enclosed_positions() {
+		let example = "\
+.F----7F7F7F7F-7....
+.|F--7||||||||FJ....
+.||.FJ||||||||L7....
+FJL7L7LJLJ||LJ.L-7..
+L--J.L7...LJS7F-7L7.
+....F-J..F7FJ|L7L7L7
+....L7.F7||L7|.L7L7|
+.....|FJLJ|FJ|F7|.LJ
+....FJL-7.||.||||...
+....L---J.LJ.LJLJ...";
+		let (_, main_loop) = parse(example);
+		assert_eq!(geometry::interior_points(&main_loop), 8);
+	}
+
+	#[test]
+	fn test_render_loop() {
+		let example = "\
+..........
+.S------7.
+.|F----7|.
+.||OOOO||.
+.||OOOO||.
+.|L-7F-J|.
+.|II||II|.
+.L--JL--J.
+..........";
+		let (grid, main_loop) = parse(example);
+		assert_eq!(geometry::interior_points(&main_loop), 4);
+		assert_eq!(
+			render_loop(&grid, &main_loop),
+			"\
+OOOOOOOOOO
+O┏──────┐O
+O│┌────┐│O
+O││OOOO││O
+O││OOOO││O
+O│└─┐┌─┘│O
+O│II││II│O
+O└──┘└──┘O
+OOOOOOOOOO
+"
+		);
+	}
+}
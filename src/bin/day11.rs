@@ -7,7 +7,7 @@ type Coordinate = [usize; 2];
 fn main() {
 	let coordinates: Vec<Coordinate> = stdin()
 		.lines()
-		.flatten()
+		.map_while(Result::ok)
 		.enumerate()
 		.flat_map(|(y, line)| {
 			line.into_bytes()
@@ -1,8 +1,11 @@
 use std::{
+	fmt::Display,
 	io::{stdin, Read},
 	str::FromStr,
 };
 
+use anyhow::Context;
+use aoc2023::{grid2d::Index, parse};
 use itertools::Itertools;
 
 fn main() {
@@ -11,57 +14,191 @@ fn main() {
 		stdin().read_to_string(&mut buf).unwrap();
 		buf
 	};
-	let patterns = input
-		.split("\n\n")
-		.map(|block| Pattern::from_str(block).unwrap())
+	let patterns = parse::blocks(&input)
+		.enumerate()
+		.map(|(i, block)| {
+			Pattern::from_str(block)
+				.with_context(|| format!("Invalid pattern {}", i + 1))
+				.unwrap()
+		})
 		.collect::<Vec<_>>();
 
-	let solve = |expected_errors: usize| {
-		patterns
-			.iter()
-			.map(|pattern| {
-				find_reflection_lines(pattern, expected_errors)
-					.exactly_one()
-					.ok()
-					.expect("Expected a single reflection line")
-			})
-			.sum::<usize>()
-	};
+	/* Pass `--smudges=N` to also solve for another amount of smudges, and `--show-reflections` to
+	 * print the reflection of every pattern with its smudges */
+	let mut smudge_budgets = vec![0, 1];
+	let mut show_reflections = false;
+	for arg in std::env::args().skip(1) {
+		if arg == "--show-reflections" {
+			show_reflections = true;
+		} else if let Some(smudges) = arg.strip_prefix("--smudges=") {
+			smudge_budgets.push(smudges.parse().unwrap());
+		}
+	}
+
+	for (i, &smudges) in smudge_budgets.iter().enumerate() {
+		let mut total = 0;
+		for (j, pattern) in patterns.iter().enumerate() {
+			match unique_reflection(pattern, smudges) {
+				Ok(reflection) => {
+					if show_reflections {
+						eprintln!("Pattern {}: {reflection}", j + 1);
+					}
+					total += reflection.summary();
+				}
+				Err(e) => eprintln!("Pattern {}: {e}", j + 1),
+			}
+		}
 
-	println!("Part 1: {}", solve(0));
-	println!("Part 2: {}", solve(1));
+		match i {
+			0 | 1 => println!("Part {}: {total}", i + 1),
+			_ => println!("{smudges} smudges: {total}"),
+		}
+	}
 }
 
-fn find_reflection_lines(
-	pattern: &Pattern,
-	expected_errors: usize,
-) -> impl Iterator<Item = usize> + '_ {
-	let vertical_lines = find_reflection_points(&pattern.columns, expected_errors);
-	let horizontal_lines = find_reflection_points(&pattern.rows, expected_errors).map(|x| x * 100);
-	horizontal_lines.chain(vertical_lines)
+/// The only reflection of the pattern with exactly `smudges` errors, or an error describing the
+/// reflections that were found instead.
+fn unique_reflection(pattern: &Pattern, smudges: usize) -> anyhow::Result<Reflection> {
+	let mut candidates = pattern.reflections().collect::<Vec<_>>();
+	candidates.sort_by_key(|reflection| reflection.errors());
+
+	let matching = candidates
+		.iter()
+		.filter(|reflection| reflection.errors() == smudges)
+		.collect::<Vec<_>>();
+	match matching[..] {
+		[reflection] => Ok(reflection.clone()),
+		[] => {
+			let closest = candidates
+				.iter()
+				.take(3)
+				.map(|reflection| {
+					format!(
+						"{} ({} errors)",
+						reflection.axis_name(),
+						reflection.errors()
+					)
+				})
+				.join(", ");
+			anyhow::bail!("No reflection with {smudges} smudges, closest are: {closest}")
+		}
+		_ => anyhow::bail!(
+			"{} reflections with {smudges} smudges: {}",
+			matching.len(),
+			matching
+				.iter()
+				.map(|reflection| reflection.axis_name())
+				.join(", ")
+		),
+	}
 }
 
-fn find_reflection_points(
-	lines: &[u32],
-	expected_errors: usize,
-) -> impl Iterator<Item = usize> + '_ {
-	let potential_reflection_lines = 1..=(lines.len() - 1);
-	potential_reflection_lines.filter(move |&i| {
-		let above = lines[..i].iter().rev();
-		let below = lines[i..].iter();
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Axis {
+	/// A horizontal line between two rows.
+	Horizontal,
+	/// A vertical line between two columns.
+	Vertical,
+}
 
-		let errors = above
-			.zip(below)
-			.map(|(x, y)| (*x ^ *y).count_ones() as usize)
-			.sum::<usize>();
-		errors == expected_errors
-	})
+/// A line of reflection, together with the cells that don't match their mirror image.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Reflection {
+	axis: Axis,
+	/// The amount of rows above or columns left of the line.
+	position: usize,
+	/// The pairs of cells that differ. Flipping either cell of a pair fixes it.
+	smudges: Vec<[Index; 2]>,
 }
 
+impl Reflection {
+	fn errors(&self) -> usize {
+		self.smudges.len()
+	}
+
+	fn summary(&self) -> usize {
+		match self.axis {
+			Axis::Horizontal => self.position * 100,
+			Axis::Vertical => self.position,
+		}
+	}
+
+	fn axis_name(&self) -> String {
+		match self.axis {
+			Axis::Horizontal => format!("below row {}", self.position),
+			Axis::Vertical => format!("right of column {}", self.position),
+		}
+	}
+}
+
+impl Display for Reflection {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", self.axis_name())?;
+		for [(x1, y1), (x2, y2)] in &self.smudges {
+			write!(f, ", smudge at ({x1}, {y1}) or ({x2}, {y2})")?;
+		}
+		Ok(())
+	}
+}
+
+/// The rows and columns of a pattern as bits, with `#` being set. Every line is split into 64-bit
+/// words, so the size of the pattern isn't limited.
 #[derive(Debug)]
 struct Pattern {
-	rows: Vec<u32>,
-	columns: Vec<u32>,
+	rows: Vec<Vec<u64>>,
+	columns: Vec<Vec<u64>>,
+}
+
+impl Pattern {
+	/// Every possible line of reflection, with the cells that don't match.
+	fn reflections(&self) -> impl Iterator<Item = Reflection> + '_ {
+		let horizontal = reflection_points(&self.rows).map(|(position, differences)| Reflection {
+			axis: Axis::Horizontal,
+			position,
+			smudges: differences
+				.into_iter()
+				.map(|(y1, y2, x)| [(x, y1), (x, y2)])
+				.collect(),
+		});
+		let vertical = reflection_points(&self.columns).map(|(position, differences)| Reflection {
+			axis: Axis::Vertical,
+			position,
+			smudges: differences
+				.into_iter()
+				.map(|(x1, x2, y)| [(x1, y), (x2, y)])
+				.collect(),
+		});
+		horizontal.chain(vertical)
+	}
+}
+
+/// Every point between two lines, with the differences between the lines mirrored around it as
+/// `(line, mirrored line, offset in the lines)`.
+fn reflection_points(
+	lines: &[Vec<u64>],
+) -> impl Iterator<Item = (usize, Vec<(usize, usize, usize)>)> + '_ {
+	(1..lines.len()).map(|i| {
+		let above = (0..i).rev();
+		let below = i..lines.len();
+
+		let differences = above
+			.zip(below)
+			.flat_map(|(a, b)| {
+				differing_bits(&lines[a], &lines[b]).map(move |offset| (a, b, offset))
+			})
+			.collect();
+		(i, differences)
+	})
+}
+
+/// The positions of the bits that differ between two lines.
+fn differing_bits<'a>(a: &'a [u64], b: &'a [u64]) -> impl Iterator<Item = usize> + 'a {
+	a.iter().zip(b).enumerate().flat_map(|(word, (a, b))| {
+		let difference = a ^ b;
+		(0..64)
+			.filter(move |bit| difference & (1 << bit) != 0)
+			.map(move |bit| word * 64 + bit)
+	})
 }
 
 impl FromStr for Pattern {
@@ -72,21 +209,145 @@ impl FromStr for Pattern {
 			Ok(match c {
 				b'#' => true,
 				b'.' => false,
-				_ => anyhow::bail!("Unexpected symbol '{}'", c),
+				_ => anyhow::bail!("Unexpected symbol '{}'", c as char),
 			})
 		}
 
-		let mut rows = vec![0; s.lines().count()];
-		let mut columns = vec![0; s.lines().next().map(|l| l.len()).unwrap_or(0)];
+		let lines = s.lines().map(str::trim).collect::<Vec<_>>();
+		let height = lines.len();
+		let width = lines.first().map(|l| l.len()).unwrap_or(0);
+		anyhow::ensure!(width > 0, "Expected a non-empty pattern");
 
-		for (y, line) in s.lines().enumerate() {
-			for (x, c) in line.trim().bytes().enumerate() {
-				let bit = bit_value(c)? as u32;
-				rows[y] |= bit << x;
-				columns[x] |= bit << y;
+		let mut rows = vec![vec![0; width.div_ceil(64)]; height];
+		let mut columns = vec![vec![0; height.div_ceil(64)]; width];
+
+		for (y, line) in lines.iter().enumerate() {
+			anyhow::ensure!(
+				line.len() == width,
+				"Expected row {} to be {width} wide, but it's {} wide",
+				y + 1,
+				line.len()
+			);
+
+			for (x, c) in line.bytes().enumerate() {
+				if bit_value(c)? {
+					rows[y][x / 64] |= 1 << (x % 64);
+					columns[x][y / 64] |= 1 << (y % 64);
+				}
 			}
 		}
 
 		Ok(Self { rows, columns })
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const EXAMPLE: [&str; 2] = [
+		"\
+#.##..##.
+..#.##.#.
+##......#
+##......#
+..#.##.#.
+..##..##.
+#.#.##.#.",
+		"\
+#...##..#
+#....#..#
+..##..###
+#####.##.
+#####.##.
+..##..###
+#....#..#",
+	];
+
+	fn summaries(smudges: usize) -> Vec<usize> {
+		EXAMPLE
+			.iter()
+			.map(|pattern| {
+				unique_reflection(&pattern.parse().unwrap(), smudges)
+					.unwrap()
+					.summary()
+			})
+			.collect()
+	}
+
+	#[test]
+	fn test_example() {
+		assert_eq!(summaries(0), vec![5, 400]);
+		assert_eq!(summaries(1), vec![300, 100]);
+	}
+
+	#[test]
+	fn test_smudges() {
+		let pattern = EXAMPLE[0].parse::<Pattern>().unwrap();
+		let reflection = unique_reflection(&pattern, 1).unwrap();
+		assert_eq!(reflection.smudges, vec![[(0, 0), (0, 5)]]);
+		assert_eq!(
+			reflection.to_string(),
+			"below row 3, smudge at (0, 0) or (0, 5)"
+		);
+
+		let pattern = EXAMPLE[1].parse::<Pattern>().unwrap();
+		assert_eq!(
+			unique_reflection(&pattern, 1).unwrap().smudges,
+			vec![[(4, 0), (4, 1)]]
+		);
+	}
+
+	#[test]
+	fn test_large_pattern() {
+		/* 100 columns and 70 rows of pseudo-random cells, mirrored between column 69 and 70 */
+		let pattern = (0..70)
+			.map(|y: usize| {
+				(0..100)
+					.map(|x: usize| {
+						let distance = if x < 70 { 69 - x } else { x - 70 };
+						if ((distance + 1) * 2654435761 + y * 40503) >> 7 & 1 == 1 {
+							'#'
+						} else {
+							'.'
+						}
+					})
+					.collect::<String>()
+			})
+			.join("\n");
+		let mut smudged = pattern.clone().into_bytes();
+		let smudge = 40 * 101 + 90;
+		smudged[smudge] = if smudged[smudge] == b'#' { b'.' } else { b'#' };
+		let smudged = String::from_utf8(smudged).unwrap();
+
+		let pattern = pattern.parse::<Pattern>().unwrap();
+		assert_eq!(unique_reflection(&pattern, 0).unwrap().summary(), 70);
+
+		let smudged = smudged.parse::<Pattern>().unwrap();
+		assert!(unique_reflection(&smudged, 0).is_err());
+		let reflection = unique_reflection(&smudged, 1).unwrap();
+		assert_eq!(reflection.summary(), 70);
+		assert_eq!(reflection.smudges, vec![[(49, 40), (90, 40)]]);
+	}
+
+	#[test]
+	fn test_diagnostics() {
+		let pattern = "#.\n.#".parse::<Pattern>().unwrap();
+		assert_eq!(
+			unique_reflection(&pattern, 0).unwrap_err().to_string(),
+			"No reflection with 0 smudges, closest are: below row 1 (2 errors), \
+			right of column 1 (2 errors)"
+		);
+
+		let pattern = "##\n##".parse::<Pattern>().unwrap();
+		assert_eq!(
+			unique_reflection(&pattern, 0).unwrap_err().to_string(),
+			"2 reflections with 0 smudges: below row 1, right of column 1"
+		);
+
+		assert_eq!(
+			"##\n#".parse::<Pattern>().unwrap_err().to_string(),
+			"Expected row 2 to be 2 wide, but it's 1 wide"
+		);
+	}
+}
@@ -1,6 +1,5 @@
 use solution::*;
 use std::{
-	collections::HashMap,
 	io::{stdin, Read},
 	str::FromStr,
 };
@@ -22,31 +21,32 @@ fn main() {
 	}
 
 	{
-		let mut platform = platform.clone();
-
-		// Is there a better key for this map? Will take ~10kb per entry this way...
-		let mut cycles = HashMap::<Platform, usize>::new();
-
 		const LIMIT: usize = 1000000000;
 
-		let mut i = 0;
-		while i < LIMIT {
-			platform.cycle();
-
-			if let Some(cycle_start) = cycles.get(&platform) {
-				let cycle_size = i - cycle_start;
-				let iterations_remaining = LIMIT - i;
-				let cycles_remaining = iterations_remaining / cycle_size;
-				let iterations_to_skip = cycles_remaining * cycle_size;
-				i += iterations_to_skip;
-			} else {
-				cycles.insert(platform.clone(), i);
-			}
+		let platform = platform
+			.clone()
+			.run_repeated(&TiltProgram::spin_cycle(), LIMIT);
+		println!("Part 2: {}", platform.total_load());
+	}
 
-			i += 1;
+	/* Pass `--program=NNE` to run another sequence of tilts, `--repeat=N` times, and print the
+	 * load on every edge afterwards */
+	let mut program = None;
+	let mut repeat = 1;
+	for arg in std::env::args().skip(1) {
+		if let Some(value) = arg.strip_prefix("--program=") {
+			program = Some(TiltProgram::from_str(value).unwrap());
+		} else if let Some(value) = arg.strip_prefix("--repeat=") {
+			repeat = value.parse().unwrap();
 		}
+	}
 
-		println!("Part 2: {}", platform.total_load());
+	if let Some(program) = program {
+		let platform = platform.run_repeated(&program, repeat);
+		eprint!("{platform}");
+		for edge in Direction::ALL {
+			println!("Load on the {edge:?} edge: {}", platform.load(edge));
+		}
 	}
 }
 
@@ -57,6 +57,8 @@ mod solution {
 		str::FromStr,
 	};
 
+	use aoc2023::cycle::find_cycle;
+
 	#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 	pub struct Platform {
 		values: Vec<Tile>,
@@ -66,10 +68,22 @@ mod solution {
 	type Idx = (usize, usize);
 
 	impl Platform {
+		/// The load on the north support beams.
 		pub fn total_load(&self) -> usize {
+			self.load(Direction::North)
+		}
+
+		/// The load on the given edge. Every round rock adds its distance to the opposite edge,
+		/// counting the rock's own row or column.
+		pub fn load(&self, edge: Direction) -> usize {
 			self.enumerate()
 				.filter(|(_, tile)| **tile == Tile::Round)
-				.map(|((_, y), _)| self.height() - y)
+				.map(|((x, y), _)| match edge {
+					Direction::North => self.height() - y,
+					Direction::West => self.width() - x,
+					Direction::South => y + 1,
+					Direction::East => x + 1,
+				})
 				.sum()
 		}
 
@@ -105,11 +119,26 @@ mod solution {
 			self.keys().zip(self.values.iter())
 		}
 
-		pub fn cycle(&mut self) {
-			self.tilt_north();
-			self.tilt_west();
-			self.tilt_south();
-			self.tilt_east();
+		/// Tilts the platform in every direction of the program, in order.
+		pub fn run(&mut self, program: &TiltProgram) {
+			for &direction in program.directions() {
+				self.tilt(direction);
+			}
+		}
+
+		/// Runs the program `times` times. Once the platform repeats itself, the remaining
+		/// complete cycles are skipped, so large counts are fine.
+		pub fn run_repeated(self, program: &TiltProgram, times: usize) -> Self {
+			find_cycle(self, times, |platform| platform.run(program))
+		}
+
+		pub fn tilt(&mut self, direction: Direction) {
+			match direction {
+				Direction::North => self.tilt_north(),
+				Direction::West => self.tilt_west(),
+				Direction::South => self.tilt_south(),
+				Direction::East => self.tilt_east(),
+			}
 		}
 
 		pub fn tilt_north(&mut self) {
@@ -258,4 +287,137 @@ mod solution {
 			Ok(())
 		}
 	}
+
+	#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+	pub enum Direction {
+		North,
+		West,
+		South,
+		East,
+	}
+
+	impl Direction {
+		pub const ALL: [Self; 4] = [Self::North, Self::West, Self::South, Self::East];
+	}
+
+	impl TryFrom<char> for Direction {
+		type Error = anyhow::Error;
+
+		fn try_from(value: char) -> Result<Self, Self::Error> {
+			Ok(match value.to_ascii_uppercase() {
+				'N' => Self::North,
+				'W' => Self::West,
+				'S' => Self::South,
+				'E' => Self::East,
+				_ => anyhow::bail!("Unexpected direction '{}'", value),
+			})
+		}
+	}
+
+	/// A sequence of tilts, written like `NWSE`.
+	#[derive(Debug, Clone, PartialEq, Eq)]
+	pub struct TiltProgram(Vec<Direction>);
+
+	impl TiltProgram {
+		/// North, then west, then south, then east.
+		pub fn spin_cycle() -> Self {
+			Self(Direction::ALL.to_vec())
+		}
+
+		pub fn directions(&self) -> &[Direction] {
+			&self.0
+		}
+	}
+
+	impl FromStr for TiltProgram {
+		type Err = anyhow::Error;
+
+		fn from_str(s: &str) -> Result<Self, Self::Err> {
+			let directions = s
+				.chars()
+				.map(Direction::try_from)
+				.collect::<anyhow::Result<Vec<_>>>()?;
+			anyhow::ensure!(!directions.is_empty(), "Expected at least one direction");
+			Ok(Self(directions))
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const EXAMPLE: &str = "\
+O....#....
+O.OO#....#
+.....##...
+OO.#O....O
+.O.....O#.
+O.#..O.#.#
+..O..#O..O
+.......O..
+#....###..
+#OO..#....";
+
+	#[test]
+	fn test_example() {
+		let platform = Platform::from_str(EXAMPLE).unwrap();
+
+		let mut tilted = platform.clone();
+		tilted.tilt_north();
+		assert_eq!(tilted.total_load(), 136);
+
+		let spun = platform.run_repeated(&TiltProgram::spin_cycle(), 1000000000);
+		assert_eq!(spun.total_load(), 64);
+	}
+
+	#[test]
+	fn test_programs() {
+		let platform = Platform::from_str(EXAMPLE).unwrap();
+		let program = |s: &str| TiltProgram::from_str(s).unwrap();
+
+		/* Tilting twice in the same direction doesn't change anything */
+		assert_eq!(
+			platform.clone().run_repeated(&program("NNE"), 1000),
+			platform.clone().run_repeated(&program("NE"), 1000)
+		);
+
+		let mut expected = platform.clone();
+		for _ in 0..3 {
+			for direction in Direction::ALL {
+				expected.tilt(direction);
+			}
+		}
+		assert_eq!(platform.clone().run_repeated(&program("nwse"), 3), expected);
+		assert_eq!(platform.clone().run_repeated(&program("NWSE"), 0), platform);
+
+		assert_eq!(
+			TiltProgram::from_str("NXE").unwrap_err().to_string(),
+			"Unexpected direction 'X'"
+		);
+		assert!(TiltProgram::from_str("").is_err());
+	}
+
+	#[test]
+	fn test_load() {
+		let mut platform = Platform::from_str(EXAMPLE).unwrap();
+		assert_eq!(platform.load(Direction::North), 104);
+
+		/* For every rock, the loads on opposite edges add up to one more than the size of the
+		 * platform */
+		platform.tilt(Direction::East);
+		let rocks = platform
+			.enumerate()
+			.filter(|(_, tile)| **tile == Tile::Round)
+			.count();
+		assert_eq!(
+			platform.load(Direction::East) + platform.load(Direction::West),
+			rocks * (platform.width() + 1)
+		);
+		assert_eq!(
+			platform.load(Direction::North) + platform.load(Direction::South),
+			rocks * (platform.height() + 1)
+		);
+		assert!(platform.load(Direction::East) > platform.load(Direction::West));
+	}
 }
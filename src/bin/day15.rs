@@ -1,11 +1,12 @@
 use std::io::stdin;
 
+use aoc2023::{interner::Interner, parse::Scanner};
 use itertools::Itertools;
 
 fn main() {
 	let input = stdin()
 		.lines()
-		.flatten()
+		.map_while(Result::ok)
 		.exactly_one()
 		.map_err(|_| anyhow::anyhow!("Expected exactly one line of input"))
 		.unwrap();
@@ -21,7 +22,8 @@ fn main() {
 		.sum::<u64>();
 	println!("Part 1: {part1}");
 
-	let mut boxes: Vec<Vec<(&str, u8)>> = vec![Vec::new(); 0xff + 1];
+	let mut labels = Interner::new();
+	let mut boxes: Vec<Vec<(u32, u8)>> = vec![Vec::new(); 0xff + 1];
 	for &Command {
 		box_nr,
 		label,
@@ -29,6 +31,7 @@ fn main() {
 		..
 	} in &commands
 	{
+		let label = labels.intern(label);
 		let box_ = &mut boxes[box_nr as usize];
 		let lens_position = box_.iter().position(|(l, _)| *l == label);
 		match action {
@@ -81,22 +84,18 @@ impl<'a> TryFrom<&'a str> for Command<'a> {
 	fn try_from(input: &'a str) -> Result<Self, Self::Error> {
 		let checksum = hash(input);
 
-		let separator_pos = input
-			.find(['-', '='])
-			.ok_or_else(|| anyhow::anyhow!("Expected '-' or '=' separator"))?;
-		let (label, input) = input.split_at(separator_pos);
+		let mut scanner = Scanner::new(input);
+		let label = scanner.word()?;
 		let box_nr = hash(label);
 
-		let (separator, value) = input.split_at(1);
-
-		let action = match separator {
-			"-" => Action::Remove,
-			"=" => {
-				let focal_length = value.parse()?;
-				Action::Add { focal_length }
-			}
-			_ => panic!(),
+		let action = if scanner.eat("-") {
+			Action::Remove
+		} else {
+			scanner.expect("=")?;
+			let focal_length = scanner.number()?;
+			Action::Add { focal_length }
 		};
+		scanner.finish()?;
 
 		Ok(Self {
 			checksum,
@@ -15,7 +15,7 @@ const DOWN: Vector2D = Vector2D(0, 1);
 fn main() {
 	let input: Grid2D<u8> = stdin()
 		.lines()
-		.flatten()
+		.map_while(Result::ok)
 		.map(|line| line.bytes().collect::<Vec<_>>())
 		.collect();
 
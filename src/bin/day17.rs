@@ -1,12 +1,13 @@
 use std::{
 	cmp::Reverse,
-	collections::{BinaryHeap, HashSet},
+	collections::{BinaryHeap, HashMap, HashSet},
 	io::stdin,
-	u8,
 };
 
+use anyhow::Context;
 use aoc2023::{
 	grid2d::{Grid2D, Grid2DGet as _},
+	parse,
 	vector2d::Vector2D,
 };
 
@@ -37,38 +38,185 @@ type Direction = (
 	u8,
 );
 
+/// How the crucible is allowed to move through the city.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct MovementRules {
+	/// The amount of blocks the crucible has to move in a straight line before it can turn or
+	/// stop.
+	min_straight: u8,
+	/// The amount of blocks the crucible can move in a straight line before it has to turn.
+	max_straight: u8,
+	/// Whether the crucible can turn around.
+	allow_reverse: bool,
+	/// The directions the crucible can initially move in.
+	start_directions: Vec<Vector2D>,
+}
+
+impl MovementRules {
+	fn crucible() -> Self {
+		Self {
+			min_straight: 1,
+			max_straight: 3,
+			allow_reverse: false,
+			start_directions: vec![EAST, SOUTH],
+		}
+	}
+
+	fn ultra_crucible() -> Self {
+		Self {
+			min_straight: 4,
+			max_straight: 10,
+			..Self::crucible()
+		}
+	}
+}
+
+const EAST: Vector2D = Vector2D(1, 0);
+const SOUTH: Vector2D = Vector2D(0, 1);
+
 fn main() {
-	let input: Grid2D<u8> = stdin()
-		.lines()
-		.flatten()
-		.map(|line| {
-			line.bytes()
-				.inspect(|b| {
-					if !b.is_ascii_digit() {
-						panic!("Not a digit");
-					}
-				})
-				.map(|b| b - b'0')
-				.collect::<Vec<_>>()
+	/* Pass `--show-path` to draw the best paths on stderr, and `--delimited` to read costs that are
+	 * separated by commas or spaces instead of single digits */
+	let show_path = std::env::args().any(|arg| arg == "--show-path");
+	let format = if std::env::args().any(|arg| arg == "--delimited") {
+		CostFormat::Delimited
+	} else {
+		CostFormat::Digits
+	};
+
+	let input = parse_input(stdin().lines().map_while(Result::ok), format).unwrap();
+
+	let start = Vector2D(0, 0);
+	let end = Vector2D::from((input.width() - 1, input.height() - 1));
+
+	for (part, rules) in [
+		(1, MovementRules::crucible()),
+		(2, MovementRules::ultra_crucible()),
+	] {
+		let cost = if show_path {
+			let path = find_best_path(&input, &rules, start, end).expect("No path found");
+			eprintln!("{}", render_path(&input, &path));
+			path.cost
+		} else {
+			find_path(&input, &rules, start, end).expect("No path found")
+		};
+		println!("Part {part}: {cost}");
+	}
+}
+
+/// How the costs of the cells are written in the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CostFormat {
+	/// A single digit per cell, like `2413`.
+	Digits,
+	/// Costs of any size, separated by anything that isn't a digit, like `24,1,30` or `24 1 30`.
+	Delimited,
+}
+
+/// Reads the cost of every cell, with every line of the input being a row.
+fn parse_input(
+	lines: impl Iterator<Item = impl AsRef<str>>,
+	format: CostFormat,
+) -> anyhow::Result<Grid2D<u32>> {
+	let rows = lines
+		.enumerate()
+		.map(|(y, line)| {
+			let line = line.as_ref();
+			match format {
+				CostFormat::Digits => line
+					.chars()
+					.map(|c| {
+						c.to_digit(10)
+							.with_context(|| format!("'{c}' is not a digit"))
+					})
+					.collect::<anyhow::Result<Vec<_>>>(),
+				CostFormat::Delimited => parse::integers(line).collect(),
+			}
+			.with_context(|| format!("Invalid costs on line {}", y + 1))
 		})
-		.collect();
+		.collect::<anyhow::Result<Vec<_>>>()?;
+
+	let width = rows.first().map_or(0, Vec::len);
+	for (y, row) in rows.iter().enumerate() {
+		anyhow::ensure!(
+			row.len() == width,
+			"Expected row {} to be {width} wide, but it's {} wide",
+			y + 1,
+			row.len()
+		);
+	}
+
+	Ok(rows.into_iter().collect())
+}
+
+/// Finds the lowest total cost of moving from `start` to `end`, where every cell costs the amount
+/// in `costs` to enter.
+fn find_path(
+	costs: &Grid2D<u32>,
+	rules: &MovementRules,
+	start: Vector2D,
+	end: Vector2D,
+) -> Option<u32> {
+	search(costs, rules, start, end, false).map(|path| path.cost)
+}
 
-	println!("Part 1: {}", find_path(&input, false));
-	println!("Part 2: {}", find_path(&input, true));
+/// Like [`find_path`], but also returns the route that was taken.
+fn find_best_path(
+	costs: &Grid2D<u32>,
+	rules: &MovementRules,
+	start: Vector2D,
+	end: Vector2D,
+) -> Option<Path> {
+	search(costs, rules, start, end, true)
 }
 
-fn find_path(input: &Grid2D<u8>, ultra: bool) -> u32 {
-	let start: Step = Step(0, (Vector2D(0, 0), (Vector2D(1, 0), 0)));
-	let target = Vector2D::from((input.width() - 1, input.height() - 1));
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Path {
+	cost: u32,
+	/// Every location after the start, with the direction in which it was entered.
+	steps: Vec<(Vector2D, Vector2D)>,
+}
+
+fn search(
+	costs: &Grid2D<u32>,
+	rules: &MovementRules,
+	start: Vector2D,
+	end: Vector2D,
+	track_path: bool,
+) -> Option<Path> {
+	/* Staying put doesn't need any moves, so the movement rules don't apply */
+	if start == end {
+		return Some(Path {
+			cost: 0,
+			steps: Vec::new(),
+		});
+	}
 
 	let mut seen: HashSet<Position> = HashSet::new();
 
-	let mut queue: BinaryHeap<Reverse<Step>> = Default::default();
-	queue.push(Reverse(start));
+	/* For every position, the cheapest way found to get there so far, and the position it came
+	 * from. Only kept when the path is requested. */
+	let mut previous: HashMap<Position, (u32, Position)> = HashMap::new();
+
+	let mut queue: BinaryHeap<Reverse<Step>> = rules
+		.start_directions
+		.iter()
+		.map(|&direction| Reverse(Step(0, (start, (direction, 0)))))
+		.collect();
 
 	while let Some(Reverse(step @ Step(cost, position @ (location, (_, steps))))) = queue.pop() {
-		if location == target && (!ultra || steps >= 4) {
-			return cost;
+		if location == end && steps >= rules.min_straight {
+			let mut steps = Vec::new();
+			if track_path {
+				let mut position = position;
+				while let Some(&(_, from)) = previous.get(&position) {
+					steps.push((position.0, position.1 .0));
+					position = from;
+				}
+				steps.reverse();
+			}
+
+			return Some(Path { cost, steps });
 		}
 
 		if seen.contains(&position) {
@@ -76,40 +224,263 @@ fn find_path(input: &Grid2D<u8>, ultra: bool) -> u32 {
 		}
 		seen.insert(position);
 
-		for neighbor in neighbors(step, input, ultra) {
+		for neighbor @ Step(neighbor_cost, neighbor_position) in neighbors(step, costs, rules) {
+			if track_path
+				&& previous
+					.get(&neighbor_position)
+					.map_or(true, |&(best, _)| neighbor_cost < best)
+			{
+				previous.insert(neighbor_position, (neighbor_cost, position));
+			}
+
 			queue.push(Reverse(neighbor));
 		}
 	}
 
-	panic!("No path found");
+	None
 }
 
-fn neighbors(
+fn neighbors<'a>(
 	Step(cost, (location, (direction, steps))): Step,
-	input: &Grid2D<u8>,
-	ultra: bool,
-) -> impl Iterator<Item = Step> {
-	let mut options: [Option<Step>; 3] = Default::default();
-
-	if !ultra || steps >= 4 {
-		for (i, direction) in [(direction.rotate_cw(), 1), (direction.rotate_ccw(), 1)]
-			.into_iter()
-			.enumerate()
-		{
-			let location = location + direction.0;
-			options[i] = input
-				.get(location)
-				.map(|&c| Step(cost + c as u32, (location, direction)));
+	costs: &'a Grid2D<u32>,
+	rules: &MovementRules,
+) -> impl Iterator<Item = Step> + 'a {
+	let mut options: [Option<Direction>; 4] = Default::default();
+
+	if steps >= rules.min_straight {
+		options[0] = Some((direction.rotate_cw(), 1));
+		options[1] = Some((direction.rotate_ccw(), 1));
+
+		if rules.allow_reverse {
+			options[2] = Some((-direction, 1));
 		}
 	}
 
-	if (ultra && steps < 10) || (!ultra && steps < 3) {
-		let direction = (direction, steps + 1);
+	if steps < rules.max_straight {
+		options[3] = Some((direction, steps + 1));
+	}
+
+	options.into_iter().flatten().filter_map(move |direction| {
 		let location = location + direction.0;
-		options[2] = input
+		costs
 			.get(location)
-			.map(|&c| Step(cost + c as u32, (location, direction)));
+			.map(|&c| Step(cost + c, (location, direction)))
+	})
+}
+
+/// Draws the costs, with the path on top of it, using arrows for the direction in which every
+/// location was entered. When any cost has more than one digit, the cells are right-aligned and
+/// separated by spaces.
+fn render_path(costs: &Grid2D<u32>, path: &Path) -> String {
+	let mut cells = Grid2D::with_size(costs.width(), costs.height());
+	for (index, cost) in costs.enumerate() {
+		cells[index] = cost.to_string();
+	}
+
+	for &(location, direction) in &path.steps {
+		cells[location] = match (direction.0, direction.1) {
+			(1, 0) => ">",
+			(-1, 0) => "<",
+			(0, -1) => "^",
+			(0, 1) => "v",
+			_ => "?",
+		}
+		.to_string();
+	}
+
+	let width = costs
+		.enumerate()
+		.map(|(_, cost)| cost.to_string().len())
+		.max()
+		.unwrap_or(1);
+	let separator = if width > 1 { " " } else { "" };
+
+	let mut output = String::new();
+	for y in 0..cells.height() {
+		let row = (0..cells.width())
+			.map(|x| format!("{:>width$}", cells[(x, y)]))
+			.collect::<Vec<_>>();
+		output.push_str(&row.join(separator));
+		output.push('\n');
+	}
+	output
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const EXAMPLE: &str = "\
+2413432311323
+3215453535623
+3255245654254
+3446585845452
+4546657867536
+1438598798454
+4457876987766
+3637877979653
+4654967986887
+4564679986453
+1224686865563
+2546548887735
+4322674655533";
+
+	fn find_path_to_corner(input: &str, rules: &MovementRules) -> Option<u32> {
+		let costs = parse_input(input.lines(), CostFormat::Digits).unwrap();
+		let end = Vector2D::from((costs.width() - 1, costs.height() - 1));
+		find_path(&costs, rules, Vector2D(0, 0), end)
+	}
+
+	#[test]
+	fn test_find_path() {
+		assert_eq!(
+			find_path_to_corner(EXAMPLE, &MovementRules::crucible()),
+			Some(102)
+		);
+		assert_eq!(
+			find_path_to_corner(EXAMPLE, &MovementRules::ultra_crucible()),
+			Some(94)
+		);
+
+		let unfortunate = "111111111111\n999999999991\n999999999991\n999999999991\n999999999991";
+		assert_eq!(
+			find_path_to_corner(unfortunate, &MovementRules::ultra_crucible()),
+			Some(71)
+		);
 	}
 
-	options.into_iter().flatten()
+	#[test]
+	fn test_find_best_path() {
+		let costs = parse_input(EXAMPLE.lines(), CostFormat::Digits).unwrap();
+		let end = Vector2D::from((costs.width() - 1, costs.height() - 1));
+		let path = find_best_path(&costs, &MovementRules::crucible(), Vector2D(0, 0), end).unwrap();
+
+		assert_eq!(path.cost, 102);
+		assert_eq!(path.steps.last().unwrap().0, end);
+		assert_eq!(
+			path.steps.iter().map(|&(l, _)| costs[l]).sum::<u32>(),
+			path.cost
+		);
+
+		let mut location = Vector2D(0, 0);
+		for &(next, direction) in &path.steps {
+			assert_eq!(location + direction, next);
+			location = next;
+		}
+	}
+
+	#[test]
+	fn test_render_path() {
+		let costs = parse_input(
+			"111111111111\n999999999991\n999999999991\n999999999991\n999999999991".lines(),
+			CostFormat::Digits,
+		)
+		.unwrap();
+		let end = Vector2D::from((costs.width() - 1, costs.height() - 1));
+		let rules = MovementRules::ultra_crucible();
+		let path = find_best_path(&costs, &rules, Vector2D(0, 0), end).unwrap();
+
+		assert_eq!(path.cost, 71);
+		assert_eq!(
+			render_path(&costs, &path),
+			"\
+1>>>>>>>1111
+9999999v9991
+9999999v9991
+9999999v9991
+9999999v>>>>
+"
+		);
+	}
+
+	#[test]
+	fn test_custom_rules() {
+		let costs = parse_input(["19111", "11191"].into_iter(), CostFormat::Digits).unwrap();
+		let rules = MovementRules {
+			min_straight: 1,
+			max_straight: 10,
+			allow_reverse: true,
+			start_directions: vec![SOUTH],
+		};
+
+		assert_eq!(
+			find_path(&costs, &rules, Vector2D(0, 0), Vector2D(4, 1)),
+			Some(7)
+		);
+		assert_eq!(
+			find_path(&costs, &rules, Vector2D(4, 1), Vector2D(0, 0)),
+			None
+		);
+
+		// Already being at the end doesn't cost anything, even when a turn isn't allowed yet
+		for rules in [rules.clone(), MovementRules::ultra_crucible()] {
+			assert_eq!(
+				find_path(&costs, &rules, Vector2D(2, 0), Vector2D(2, 0)),
+				Some(0)
+			);
+		}
+
+		/* Heading east in a single row, the only way to reach the west end is to turn around */
+		let row = parse_input(["11111"].into_iter(), CostFormat::Digits).unwrap();
+		let east = MovementRules {
+			start_directions: vec![EAST],
+			..rules
+		};
+		let no_reverse = MovementRules {
+			allow_reverse: false,
+			..east.clone()
+		};
+		assert_eq!(
+			find_path(&row, &east, Vector2D(2, 0), Vector2D(0, 0)),
+			Some(4)
+		);
+		assert_eq!(
+			find_path(&row, &no_reverse, Vector2D(2, 0), Vector2D(0, 0)),
+			None
+		);
+	}
+
+	#[test]
+	fn test_delimited_costs() {
+		let costs = parse_input(["1,20,3", "4 5 60"].into_iter(), CostFormat::Delimited).unwrap();
+		assert_eq!(costs[Vector2D(1, 0)], 20);
+		assert_eq!(costs[Vector2D(2, 1)], 60);
+		assert_eq!(
+			find_path(
+				&costs,
+				&MovementRules::crucible(),
+				Vector2D(0, 0),
+				Vector2D(2, 1)
+			),
+			Some(69)
+		);
+
+		let path = find_best_path(
+			&costs,
+			&MovementRules::crucible(),
+			Vector2D(0, 0),
+			Vector2D(2, 1),
+		)
+		.unwrap();
+		assert_eq!(render_path(&costs, &path), " 1 20  3\n v  >  >\n");
+
+		// The format is the same for the whole input, so an all-digit row isn't split into digits
+		let costs = parse_input(["12", "34"].into_iter(), CostFormat::Delimited).unwrap();
+		assert_eq!((costs.width(), costs[Vector2D(0, 1)]), (1, 34));
+		let error = |lines: [&str; 2], format| {
+			format!("{:#}", parse_input(lines.into_iter(), format).unwrap_err())
+		};
+		assert_eq!(
+			error(["12", "3,4"], CostFormat::Digits),
+			"Invalid costs on line 2: ',' is not a digit"
+		);
+		assert_eq!(
+			error(["1,2,3", "4,5"], CostFormat::Delimited),
+			"Expected row 2 to be 3 wide, but it's 2 wide"
+		);
+		assert_eq!(
+			error(["1", "-2"], CostFormat::Delimited),
+			"Invalid costs on line 2: Invalid integer '-2' in '-2': invalid digit found in string"
+		);
+	}
 }
@@ -1,7 +1,6 @@
 use std::io::stdin;
 
-use aoc2023::vector2d::Vector2D;
-use itertools::Itertools;
+use aoc2023::{geometry, vector2d::Vector2D};
 
 const RIGHT: Vector2D = Vector2D(1, 0);
 const LEFT: Vector2D = Vector2D(-1, 0);
@@ -14,7 +13,7 @@ type Instruction = (Vector2D, usize);
 fn main() {
 	let input: Vec<(Instruction, Instruction)> = stdin()
 		.lines()
-		.flatten()
+		.map_while(Result::ok)
 		.map(|line| {
 			let mut line = line.split_whitespace();
 
@@ -66,26 +65,6 @@ fn calculate_area<'a>(instructions: impl Iterator<Item = &'a Instruction> + 'a)
 		})
 		.collect::<Vec<_>>();
 
-	let edges = || {
-		positions
-			.iter()
-			.cycle()
-			.take(positions.len() + 1)
-			.tuple_windows()
-	};
-
-	let max_y = positions.iter().map(|Vector2D(_, y)| *y).max().unwrap_or(0);
-	let area = edges()
-		.map(|(Vector2D(x0, y0), Vector2D(x1, _))| {
-			let x_diff = x1 - x0;
-			let y_diff = max_y - y0;
-			x_diff * y_diff
-		})
-		.sum::<isize>() as usize;
-
-	let border_length = edges()
-		.map(|(&a, &b)| a.manhattan_distance_to(b))
-		.sum::<usize>();
-
-	area + (border_length / 2) + 1
+	// The trench itself is dug out too, so the cubes on the border count as well
+	geometry::lattice_points(&positions)
 }
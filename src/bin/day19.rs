@@ -1,11 +1,19 @@
 use std::{
-	collections::HashMap,
 	io::{stdin, Read},
 	ops::Range,
-	str::FromStr,
 };
 
-use regex::Regex;
+use anyhow::Context;
+use aoc2023::{
+	graph::Graph,
+	hyperbox::Hyperbox,
+	interner::Interner,
+	parse::{self, Scanner},
+};
+use itertools::Itertools;
+
+/// The values every field of a part can have, unless `--domain=START..END` is passed.
+const DEFAULT_DOMAIN: Range<i64> = 1..4001;
 
 fn main() {
 	let input = {
@@ -14,187 +22,528 @@ fn main() {
 		buf
 	};
 
-	let (rules, parts) = input.split_once("\n\n").unwrap();
-	let rules = parse_rules(rules);
-	let parts = parse_parts(parts);
-
-	let part1 = parts
-		.filter(|&part| {
-			let mut rule = rules[0];
-
-			'outer: loop {
-				let result = rule.apply(part);
-				match result {
-					RuleResult::End(v) => return v,
-					RuleResult::Jump(i) => {
-						rule = rules[i];
-						continue 'outer;
-					}
+	// Pass `--show-tree` to print the decision tree and the accepted ranges, or `--dot` to export it
+	let (mut show_tree, mut show_dot, mut domain) = (false, false, DEFAULT_DOMAIN);
+	for arg in std::env::args().skip(1) {
+		match arg.as_str() {
+			"--show-tree" => show_tree = true,
+			"--dot" => show_dot = true,
+			_ => {
+				if let Some(value) = arg.strip_prefix("--domain=") {
+					let [start, end] = parse::ints(value).unwrap();
+					assert!(
+						start < end,
+						"Expected a non-empty domain, got {start}..{end}"
+					);
+					domain = start..end;
 				}
 			}
-		})
+		}
+	}
+
+	let (rules, parts) = parse::blocks(&input).collect_tuple().unwrap();
+	let mut fields = Interner::new();
+	let workflows = parse_rules(rules, &mut fields, &domain).unwrap();
+	let parts = parse_parts(parts, &mut fields, &domain).unwrap();
+
+	let all_parts = all_parts(fields.len(), &domain);
+	let tree = DecisionTree::compile(&workflows)
+		.unwrap()
+		.simplify(&all_parts);
+	let accepted = tree.accepted_regions(all_parts);
+
+	if show_tree {
+		eprintln!("{}", tree.render(&fields, &domain));
+		for region in &accepted {
+			eprintln!("A: {}", format_region(region, &fields));
+		}
+	}
+	if show_dot {
+		eprint!("{}", tree.to_dot(&fields, &domain));
+	}
+
+	let part1 = parts
+		.iter()
+		.filter(|part| tree.evaluate(part))
 		.map(|part| part.value())
-		.sum::<usize>();
+		.sum::<i64>();
 	println!("Part 1: {part1}");
 
-	println!("Part 2: {}", find_part_combination_count(&rules));
+	let part2 = count_combinations(&accepted).unwrap();
+	println!("Part 2: {part2}");
 }
 
-fn find_part_combination_count(rules: &[Rule]) -> usize {
-	fn find_part_combination_count(pp: PossibleParts, rules: &[Rule], rule: Rule) -> usize {
-		if pp.is_empty() {
-			return 0;
+/// The workflows, compiled into a single binary tree of conditions. Every path from the root ends
+/// in whether the part is accepted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum DecisionTree {
+	Leaf(bool),
+	Split {
+		condition: RuleCondition,
+		matched: Box<DecisionTree>,
+		unmatched: Box<DecisionTree>,
+	},
+}
+
+impl DecisionTree {
+	/// Inlines all the jumps of the rules, starting at the first one. Fails when the workflows can
+	/// jump back to a rule they came from, as a part could then loop forever.
+	fn compile(workflows: &Workflows) -> anyhow::Result<Self> {
+		/* `path` holds the rules that lead to the current one */
+		fn compile_result(
+			workflows: &Workflows,
+			result: RuleResult,
+			path: &mut Vec<usize>,
+		) -> anyhow::Result<DecisionTree> {
+			match result {
+				RuleResult::End(accepted) => Ok(DecisionTree::Leaf(accepted)),
+				RuleResult::Jump(i) => compile_rule(workflows, i, path),
+			}
 		}
 
-		let apply_rule_result = |pp: PossibleParts, rule_result: RuleResult| -> usize {
-			match rule_result {
-				RuleResult::End(false) => 0,
-				RuleResult::End(true) => pp.combinations(),
-				RuleResult::Jump(destination) => {
-					find_part_combination_count(pp, rules, rules[destination])
-				}
+		fn compile_rule(
+			workflows: &Workflows,
+			rule: usize,
+			path: &mut Vec<usize>,
+		) -> anyhow::Result<DecisionTree> {
+			if let Some(position) = path.iter().position(|&r| r == rule) {
+				let cycle = path[position..]
+					.iter()
+					.map(|&r| workflows.label(r))
+					.dedup()
+					.join(" -> ");
+				anyhow::bail!(
+					"The workflows loop forever: {cycle} -> {}",
+					workflows.label(rule)
+				);
 			}
+
+			path.push(rule);
+			let tree = match &workflows.rules[rule] {
+				Rule::Always(result) => compile_result(workflows, *result, path)?,
+				Rule::Conditional {
+					condition,
+					matched,
+					unmatched,
+				} => DecisionTree::Split {
+					condition: condition.clone(),
+					matched: Box::new(compile_result(workflows, *matched, path)?),
+					unmatched: Box::new(compile_result(workflows, *unmatched, path)?),
+				},
+			};
+			path.pop();
+
+			Ok(tree)
+		}
+
+		compile_rule(workflows, 0, &mut Vec::new())
+	}
+
+	/// Removes the conditions that can't be false or can't be true for the given parts, and merges
+	/// conditions of which both outcomes are the same.
+	fn simplify(self, parts: &PossibleParts) -> Self {
+		let Self::Split {
+			condition,
+			matched,
+			unmatched,
+		} = self
+		else {
+			return self;
+		};
+
+		let (parts_matched, parts_unmatched) = condition.split(parts);
+		let Some(parts_matched) = parts_matched else {
+			return unmatched.simplify(parts);
+		};
+		/* When the condition lies in the middle of the field's values, the parts that don't match
+		 * it are in two pieces. Those are simplified as a whole, which is less thorough but still
+		 * correct. */
+		let parts_unmatched = match &parts_unmatched[..] {
+			[] => return matched.simplify(&parts_matched),
+			[single] => single,
+			_ => parts,
 		};
 
-		match rule {
-			Rule::Always(result) => apply_rule_result(pp, result),
-			Rule::Conditional {
+		let matched = matched.simplify(&parts_matched);
+		let unmatched = unmatched.simplify(parts_unmatched);
+		if matched == unmatched {
+			return matched;
+		}
+
+		Self::Split {
+			condition,
+			matched: Box::new(matched),
+			unmatched: Box::new(unmatched),
+		}
+	}
+
+	fn evaluate(&self, part: &Part) -> bool {
+		match self {
+			Self::Leaf(accepted) => *accepted,
+			Self::Split {
 				condition,
-				left,
-				right,
-			} => pp
-				.split(condition)
-				.into_iter()
-				.zip([left, right])
-				.map(|(pp, result)| apply_rule_result(pp, result))
-				.sum(),
+				matched,
+				unmatched,
+			} => {
+				if condition.evaluate(part) {
+					matched.evaluate(part)
+				} else {
+					unmatched.evaluate(part)
+				}
+			}
+		}
+	}
+
+	/// Splits the parts into the disjoint regions that end up in accepting leaves.
+	fn accepted_regions(&self, parts: PossibleParts) -> Vec<PossibleParts> {
+		fn collect(tree: &DecisionTree, parts: PossibleParts, regions: &mut Vec<PossibleParts>) {
+			if parts.is_empty() {
+				return;
+			}
+
+			match tree {
+				DecisionTree::Leaf(true) => regions.push(parts),
+				DecisionTree::Leaf(false) => {}
+				DecisionTree::Split {
+					condition,
+					matched,
+					unmatched,
+				} => {
+					let (parts_matched, parts_unmatched) = condition.split(&parts);
+					if let Some(parts_matched) = parts_matched {
+						collect(matched, parts_matched, regions);
+					}
+					for parts_unmatched in parts_unmatched {
+						collect(unmatched, parts_unmatched, regions);
+					}
+				}
+			}
 		}
+
+		let mut regions = Vec::new();
+		collect(self, parts, &mut regions);
+		regions
 	}
 
-	find_part_combination_count(PossibleParts::new(), rules, rules[0])
+	/// An indented outline of the tree, with the names of the fields taken from `fields`.
+	fn render(&self, fields: &Interner, domain: &Range<i64>) -> String {
+		fn write(
+			tree: &DecisionTree,
+			fields: &Interner,
+			domain: &Range<i64>,
+			depth: usize,
+			output: &mut String,
+		) {
+			match tree {
+				DecisionTree::Leaf(accepted) => {
+					output.push_str(if *accepted { "A\n" } else { "R\n" });
+				}
+				DecisionTree::Split {
+					condition,
+					matched,
+					unmatched,
+				} => {
+					let indent = " ".repeat(depth * 2 + 2);
+					output.push_str(&format!(
+						"{}\n{indent}yes: ",
+						condition.render(fields, domain)
+					));
+					write(matched, fields, domain, depth + 1, output);
+					output.push_str(&format!("{indent}no: "));
+					write(unmatched, fields, domain, depth + 1, output);
+				}
+			}
+		}
+
+		let mut output = String::new();
+		write(self, fields, domain, 0, &mut output);
+		output
+	}
+
+	fn to_dot(&self, fields: &Interner, domain: &Range<i64>) -> String {
+		fn add(
+			tree: &DecisionTree,
+			fields: &Interner,
+			domain: &Range<i64>,
+			graph: &mut Graph<String, &'static str>,
+		) -> usize {
+			match tree {
+				DecisionTree::Leaf(accepted) => {
+					graph.add_node(if *accepted { "A" } else { "R" }.to_string())
+				}
+				DecisionTree::Split {
+					condition,
+					matched,
+					unmatched,
+				} => {
+					let node = graph.add_node(condition.render(fields, domain));
+					let matched = add(matched, fields, domain, graph);
+					let unmatched = add(unmatched, fields, domain, graph);
+					graph.add_edge(node, matched, "yes");
+					graph.add_edge(node, unmatched, "no");
+					node
+				}
+			}
+		}
+
+		let mut graph = Graph::new();
+		add(self, fields, domain, &mut graph);
+		graph.to_dot(|label| label.clone(), |label| label.to_string())
+	}
 }
 
-fn parse_rules(input: &str) -> Vec<Rule> {
-	let mut lines = input.lines().collect::<Vec<_>>();
+/// Formats the ranges of every field, like `x=1..=1415 m=1..=4000 a=1..=2005 s=1351..=4000`.
+fn format_region(region: &PossibleParts, fields: &Interner) -> String {
+	fields
+		.iter()
+		.zip(&region.0)
+		.map(|((_, name), range)| format!("{name}={}..={}", range.start, range.end - 1))
+		.join(" ")
+}
+
+/// Parses the workflows into a single list of rules, in which every conditional rule continues
+/// with the next one when it doesn't match. The names of the fields used in conditions are added to
+/// `fields`, and the ranges of the conditions are limited to `domain`.
+fn parse_rules(
+	input: &str,
+	fields: &mut Interner,
+	domain: &Range<i64>,
+) -> anyhow::Result<Workflows> {
+	let mut workflows = input
+		.lines()
+		.enumerate()
+		.map(|(i, line)| {
+			let parse_workflow = || -> anyhow::Result<(&str, Vec<&str>)> {
+				let mut scanner = Scanner::new(line);
+				let label = scanner.word()?;
+				scanner.expect("{")?;
+				let rules = scanner.until("}")?;
+				scanner.finish()?;
+				Ok((label, rules.split(',').collect()))
+			};
+			parse_workflow().with_context(|| format!("Invalid workflow on line {}", i + 1))
+		})
+		.collect::<anyhow::Result<Vec<_>>>()?;
 
 	/* This makes working with the graph easier, because the starting rule will now always be the
 	 * first one in the resulting Vec. */
-	let start_position = lines
+	let start_position = workflows
 		.iter()
-		.position(|line| line.starts_with("in{"))
-		.expect("Expected a start workflow");
-	lines.swap(0, start_position);
+		.position(|(label, _)| *label == "in")
+		.context("Expected a start workflow")?;
+	workflows.swap(0, start_position);
 
-	/* Map from the rule's label to it's position */
-	let label_map = lines
+	let labels = workflows
 		.iter()
-		.scan(0, |acc, line| {
-			let start = *acc;
-
-			let (label, rest) = line.split_once('{').unwrap();
-			let rule_count = rest.split(',').count();
-
-			*acc += rule_count;
+		.map(|(label, _)| *label)
+		.collect::<Interner>();
 
-			Some((label, start))
-		})
-		.collect::<HashMap<_, _>>();
-
-	lines
+	/* The position of every workflow's first rule, indexed by the id of its label */
+	let workflow_starts = workflows
 		.iter()
-		.flat_map(|line| {
-			let (_, rules) = line.split_once('{').unwrap();
-			let rules = &rules[..(rules.len() - 1)];
-			rules.split(',')
+		.scan(0, |acc, (_, rules)| {
+			let start = *acc;
+			*acc += rules.len();
+			Some(start)
 		})
-		.enumerate()
-		.map(|(i, rule)| {
-			lazy_static::lazy_static! {
-				static ref PREDICATE_PATTERN: Regex = Regex::new(r"^([xmas])([<>])(\d+):(\w+)$").unwrap();
+		.collect::<Vec<_>>();
+
+	let parse_destination = |destination: &str| -> anyhow::Result<RuleResult> {
+		match destination {
+			"A" => Ok(RuleResult::End(true)),
+			"R" => Ok(RuleResult::End(false)),
+			label => {
+				let id = labels
+					.get(label)
+					.with_context(|| format!("Unknown workflow '{label}'"))?;
+				Ok(RuleResult::Jump(workflow_starts[id as usize]))
 			}
+		}
+	};
 
-			let parse_destination = |destination: &str| -> RuleResult {
-				match destination {
-					"A" => RuleResult::End(true),
-					"R" => RuleResult::End(false),
-					label => RuleResult::Jump(label_map[label]),
-				}
-			};
-
-			if let Some(captures) = PREDICATE_PATTERN.captures(rule) {
-				let field = captures.get(1).unwrap().as_str();
-				let field = Part::label_offset(field.as_bytes()[0]);
+	let mut result = Vec::new();
+	for (label, rules) in &workflows {
+		for rule in rules {
+			let next_rule = RuleResult::Jump(result.len() + 1);
+			let rule = parse_rule(rule, next_rule, fields, domain, &parse_destination)
+				.with_context(|| format!("Invalid rule '{rule}' in workflow '{label}'"))?;
+			result.push(rule);
+		}
+	}
 
-				let split_point = captures.get(3).unwrap().as_str();
-				let split_point = split_point.parse::<u16>().unwrap();
+	Ok(Workflows {
+		rules: result,
+		starts: workflow_starts
+			.into_iter()
+			.zip(workflows.iter().map(|(label, _)| label.to_string()))
+			.collect(),
+	})
+}
 
-				let destination = captures.get(4).unwrap().as_str();
-				let destination = parse_destination(destination);
+/// Parses a rule like `a<2006:qkq`, `x==5:A` or just `rfg`.
+fn parse_rule(
+	rule: &str,
+	next_rule: RuleResult,
+	fields: &mut Interner,
+	domain: &Range<i64>,
+	parse_destination: &impl Fn(&str) -> anyhow::Result<RuleResult>,
+) -> anyhow::Result<Rule> {
+	let mut scanner = Scanner::new(rule);
+	let word = scanner.word()?;
+
+	if scanner.is_empty() {
+		return Ok(Rule::Always(parse_destination(word)?));
+	}
 
-				let operator = captures.get(2).unwrap().as_str();
-				let operator = operator.as_bytes()[0];
+	/* The two-character operators go first, so `<` doesn't match the start of `<=` */
+	let operator = ["<=", ">=", "==", "<", ">"]
+		.into_iter()
+		.find(|operator| scanner.eat(operator))
+		.ok_or_else(|| scanner.error("Expected one of '<', '<=', '>', '>=' or '=='"))?;
+	let value = scanner.number::<i64>()?;
+	scanner.expect(":")?;
+	let destination = parse_destination(scanner.word()?)?;
+	scanner.finish()?;
+
+	let clamp = |value: i64| value.clamp(domain.start, domain.end);
+	let range = match operator {
+		"<" => domain.start..clamp(value),
+		"<=" => domain.start..clamp(value.saturating_add(1)),
+		">" => clamp(value.saturating_add(1))..domain.end,
+		">=" => clamp(value)..domain.end,
+		"==" => clamp(value)..clamp(value.saturating_add(1)),
+		_ => unreachable!(),
+	};
 
-				let next_rule = RuleResult::Jump(i + 1);
-				let (split_point, left, right) = match operator {
-					b'<' => (split_point, destination, next_rule),
-					b'>' => (split_point + 1, next_rule, destination),
-					_ => panic!("Unexpected operator '{operator}'"),
-				};
+	Ok(Rule::Conditional {
+		condition: RuleCondition {
+			field: fields.intern(word) as usize,
+			range,
+		},
+		matched: destination,
+		unmatched: next_rule,
+	})
+}
 
-				Rule::Conditional {
-					condition: RuleCondition { field, split_point },
-					left,
-					right,
+/// Parses parts like `{x=787,m=2655,a=1222,s=2876}`. The fields can be in any order, but every
+/// part needs a value in `domain` for every field, including the ones only used in the rules.
+fn parse_parts(
+	input: &str,
+	fields: &mut Interner,
+	domain: &Range<i64>,
+) -> anyhow::Result<Vec<Part>> {
+	let values = input
+		.lines()
+		.map(|line| {
+			let mut parse_part = || -> anyhow::Result<Vec<(usize, i64)>> {
+				let mut scanner = Scanner::new(line);
+				scanner.expect("{")?;
+
+				let mut values = Vec::new();
+				loop {
+					let field = fields.intern(scanner.word()?) as usize;
+					scanner.expect("=")?;
+					values.push((field, scanner.number()?));
+					if !scanner.eat(",") {
+						break;
+					}
 				}
-			} else {
-				let destination = parse_destination(rule);
-				Rule::Always(destination)
+
+				scanner.expect("}")?;
+				scanner.finish()?;
+				Ok(values)
+			};
+			parse_part().with_context(|| format!("Invalid part '{line}'"))
+		})
+		.collect::<anyhow::Result<Vec<_>>>()?;
+
+	/* Only now all the fields are known */
+	values
+		.into_iter()
+		.zip(input.lines())
+		.map(|(values, line)| {
+			let mut part = vec![None; fields.len()];
+			for (field, value) in values {
+				part[field] = Some(value);
 			}
+
+			part.into_iter()
+				.zip(fields.iter())
+				.map(|(value, (_, name))| {
+					let value = value
+						.with_context(|| format!("Part '{line}' has no value for '{name}'"))?;
+					anyhow::ensure!(
+						domain.contains(&value),
+						"Part '{line}' has {name}={value}, which is outside of {}..{}",
+						domain.start,
+						domain.end
+					);
+					Ok(value)
+				})
+				.collect::<anyhow::Result<_>>()
+				.map(Part)
 		})
 		.collect()
 }
 
-fn parse_parts(input: &str) -> impl Iterator<Item = Part> + '_ {
-	input.lines().map(|line| line.parse().unwrap())
+/// The rules of all workflows, with the start workflow's rules first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Workflows {
+	rules: Vec<Rule>,
+	/// The position of every workflow's first rule together with its label, in order.
+	starts: Vec<(usize, String)>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+impl Workflows {
+	/// The label of the workflow that contains the rule.
+	fn label(&self, rule: usize) -> &str {
+		let workflow = self.starts.partition_point(|(start, _)| *start <= rule) - 1;
+		&self.starts[workflow].1
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 enum Rule {
 	Always(RuleResult),
 	Conditional {
 		condition: RuleCondition,
-		left: RuleResult,
-		right: RuleResult,
+		matched: RuleResult,
+		unmatched: RuleResult,
 	},
 }
 
-impl Rule {
-	fn apply(&self, part: Part) -> RuleResult {
-		match *self {
-			Self::Always(result) => result,
-			Self::Conditional {
-				condition,
-				left,
-				right,
-			} => {
-				if condition.evaluate(part) {
-					left
-				} else {
-					right
-				}
-			}
-		}
-	}
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Matches the parts of which the field lies within the range. The range never exceeds the domain
+/// of the values.
+#[derive(Debug, Clone, PartialEq, Eq)]
 struct RuleCondition {
 	field: usize,
-	split_point: u16,
+	range: Range<i64>,
 }
+
 impl RuleCondition {
-	fn evaluate(&self, part: Part) -> bool {
-		part.0[self.field] < self.split_point
+	fn evaluate(&self, part: &Part) -> bool {
+		self.range.contains(&part.0[self.field])
+	}
+
+	/// The parts that match the condition, if there are any, and the parts that don't. The latter
+	/// can be in up to two pieces, below and above the condition's range.
+	fn split(&self, parts: &PossibleParts) -> (Option<PossibleParts>, Vec<PossibleParts>) {
+		let mut matching = parts.clone();
+		matching.0[self.field] = self.range.clone();
+
+		(parts.intersection(&matching), parts.difference(&matching))
+	}
+
+	/// Formats the condition like it would be written in the rules, like `x<1416` or `m==5`. A
+	/// range that starts or ends at the edge of `domain` is written as a comparison.
+	fn render(&self, fields: &Interner, domain: &Range<i64>) -> String {
+		let name = &fields[self.field as u32];
+		match (self.range.start, self.range.end) {
+			(start, end) if start == domain.start => format!("{name}<{end}"),
+			(start, end) if end == domain.end => format!("{name}>{}", start - 1),
+			(start, end) if end - start == 1 => format!("{name}=={start}"),
+			(start, end) => format!("{name} in {start}..{end}"),
+		}
 	}
 }
 
@@ -204,65 +553,253 @@ enum RuleResult {
 	Jump(usize),
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-struct PossibleParts([Range<u16>; 4]);
+/// Every dimension represents one of the fields of a `Part`.
+type PossibleParts = Hyperbox<Vec<Range<i64>>>;
 
-impl PossibleParts {
-	fn new() -> Self {
-		Self([1..4001, 1..4001, 1..4001, 1..4001])
-	}
+/// All the parts of which every field lies in `domain`.
+fn all_parts(fields: usize, domain: &Range<i64>) -> PossibleParts {
+	Hyperbox(vec![domain.clone(); fields])
+}
+
+/// The total amount of parts in the disjoint regions. A large domain with many fields can make
+/// this too large to count, even in 128 bits.
+fn count_combinations(regions: &[PossibleParts]) -> anyhow::Result<u128> {
+	regions
+		.iter()
+		.try_fold(0u128, |total, region| total.checked_add(region.volume()?))
+		.context("Too many combinations to count")
+}
 
-	fn split(&self, RuleCondition { field, split_point }: RuleCondition) -> [Self; 2] {
-		let (mut left, mut right) = (self.0.clone(), self.0.clone());
-		left[field] = left[field].start..split_point;
-		right[field] = split_point..right[field].end;
+/// The values of a part, indexed by the ids of the fields.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Part(Vec<i64>);
 
-		[Self(left), Self(right)]
+impl Part {
+	fn value(&self) -> i64 {
+		self.0.iter().sum()
 	}
+}
 
-	fn combinations(&self) -> usize {
-		self.0.iter().map(|r| r.len()).product()
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const EXAMPLE: &str = "\
+px{a<2006:qkq,m>2090:A,rfg}
+pv{a>1716:R,A}
+lnx{m>1548:A,A}
+rfg{s<537:gd,x>2440:R,A}
+qs{s>3448:A,lnx}
+qkq{x<1416:A,crn}
+crn{x>2662:A,R}
+in{s<1351:px,qqz}
+qqz{s>2770:qs,m<1801:hdj,R}
+gd{a>3333:R,R}
+hdj{m>838:A,pv}";
+
+	fn compile(rules: &str, fields: &mut Interner, domain: &Range<i64>) -> DecisionTree {
+		DecisionTree::compile(&parse_rules(rules, fields, domain).unwrap()).unwrap()
 	}
 
-	fn is_empty(&self) -> bool {
-		self.combinations() == 0
+	fn combinations(tree: &DecisionTree, all_parts: PossibleParts) -> u128 {
+		count_combinations(&tree.accepted_regions(all_parts)).unwrap()
 	}
-}
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Part([u16; 4]);
+	#[test]
+	fn test_simplify() {
+		let mut fields = Interner::new();
+		let tree = compile(EXAMPLE, &mut fields, &DEFAULT_DOMAIN);
+		let all_parts = all_parts(fields.len(), &DEFAULT_DOMAIN);
+		let simplified = tree.clone().simplify(&all_parts);
+
+		// `lnx` and `gd` always end the same way, so their conditions are gone
+		let rendered = simplified.render(&fields, &DEFAULT_DOMAIN);
+		assert!(!rendered.contains("m>1548"));
+		assert!(!rendered.contains("a>3333"));
+		assert!(rendered.len() < tree.render(&fields, &DEFAULT_DOMAIN).len());
+
+		assert_eq!(combinations(&tree, all_parts.clone()), 167409079868000);
+		assert_eq!(combinations(&simplified, all_parts), 167409079868000);
+	}
 
-impl Part {
-	fn value(&self) -> usize {
-		self.0.iter().fold(0usize, |acc, &it| acc + it as usize)
+	#[test]
+	fn test_evaluate() {
+		let mut fields = Interner::new();
+		let tree = compile(EXAMPLE, &mut fields, &DEFAULT_DOMAIN);
+		let tree = tree.simplify(&all_parts(fields.len(), &DEFAULT_DOMAIN));
+		let parts = "\
+{x=787,m=2655,a=1222,s=2876}
+{x=1679,m=44,a=2067,s=496}
+{x=2036,m=264,a=79,s=2244}
+{x=2461,m=1339,a=466,s=291}
+{x=2127,m=1623,a=2188,s=1013}";
+
+		let accepted = parse_parts(parts, &mut fields, &DEFAULT_DOMAIN)
+			.unwrap()
+			.iter()
+			.map(|part| tree.evaluate(part))
+			.collect::<Vec<_>>();
+		assert_eq!(accepted, vec![true, false, true, false, true]);
 	}
 
-	fn label_offset(label: u8) -> usize {
-		match label {
-			b'x' => 0,
-			b'm' => 1,
-			b'a' => 2,
-			b's' => 3,
-			_ => panic!(),
-		}
+	#[test]
+	fn test_display() {
+		let mut fields = Interner::new();
+		let tree = compile("in{x<10:A,m>20:R,A}", &mut fields, &DEFAULT_DOMAIN);
+		assert_eq!(
+			tree.render(&fields, &DEFAULT_DOMAIN),
+			"x<10\n  yes: A\n  no: m>20\n    yes: R\n    no: A\n"
+		);
+
+		let all_parts = all_parts(fields.len(), &DEFAULT_DOMAIN);
+		assert_eq!(
+			format_region(&tree.accepted_regions(all_parts)[0], &fields),
+			"x=1..=9 m=1..=4000"
+		);
+		assert!(tree
+			.to_dot(&fields, &DEFAULT_DOMAIN)
+			.contains("[label=\"m>20\"]"));
+	}
+
+	#[test]
+	fn test_comparisons() {
+		let mut fields = Interner::new();
+		let domain = 1..6;
+		let tree = compile(
+			"in{width>=3:check,R}\ncheck{depth==2:R,depth<=3:A,colour>1:A,R}",
+			&mut fields,
+			&domain,
+		);
+		assert_eq!(
+			fields.iter().map(|(_, name)| name).collect::<Vec<_>>(),
+			vec!["width", "depth", "colour"]
+		);
+		assert!(tree.render(&fields, &domain).contains("depth==2"));
+
+		/* A width of 3 to 5, and either a depth of 1 or 3 with any colour, or a depth of 4 or 5
+		 * with a colour of 2 to 5. */
+		let all_parts = all_parts(fields.len(), &domain);
+		assert_eq!(combinations(&tree, all_parts.clone()), 3 * (2 * 5 + 2 * 4));
+		assert_eq!(
+			combinations(&tree.clone().simplify(&all_parts), all_parts),
+			3 * (2 * 5 + 2 * 4)
+		);
+
+		let parts = parse_parts(
+			"{width=3,depth=2,colour=5}\n{colour=2,depth=5,width=4}",
+			&mut fields,
+			&domain,
+		)
+		.unwrap();
+		assert!(!tree.evaluate(&parts[0]));
+		assert!(tree.evaluate(&parts[1]));
+		assert_eq!(parts[1].value(), 11);
 	}
-}
 
-impl FromStr for Part {
-	type Err = anyhow::Error;
+	#[test]
+	fn test_domain() {
+		let mut fields = Interner::new();
+		let domain = 1..6;
+		let tree = compile("in{x>3:A,x<=1:A,x>9:A,R}", &mut fields, &domain);
+		assert_eq!(
+			tree.render(&fields, &domain),
+			"x>3\n  yes: A\n  no: x<2\n    yes: A\n    no: x>5\n      yes: A\n      no: R\n"
+		);
+		assert_eq!(combinations(&tree, all_parts(1, &domain)), 3);
+
+		// The upper end of the domain is included by `>`
+		let domain = 0..i64::MAX;
+		let tree = compile("in{x>5:A,R}", &mut fields, &domain);
+		assert_eq!(
+			combinations(&tree, all_parts(1, &domain)),
+			i64::MAX as u128 - 6
+		);
+		let parts = parse_parts(&format!("{{x={}}}", i64::MAX - 1), &mut fields, &domain).unwrap();
+		assert!(tree.evaluate(&parts[0]));
+
+		let error = parse_parts("{x=6}", &mut fields, &(1..6)).unwrap_err();
+		assert_eq!(
+			format!("{error:#}"),
+			"Part '{x=6}' has x=6, which is outside of 1..6"
+		);
+
+		// A much larger domain doesn't overflow
+		let domain = -1_000_000..1_000_000;
+		let tree = compile(EXAMPLE, &mut Interner::new(), &domain);
+		assert!(combinations(&tree, all_parts(4, &domain)) > 2_000_000u128.pow(3));
+
+		// Any amount of fields works, as long as the combinations can be counted
+		let rules = "in{f1<0:R,f2<0:R,f3<0:R,f4<0:R,f5<0:R,f6<0:R,f7<0:R,f8<0:R,f9>2:R,A}";
+		let mut fields = Interner::new();
+		let tree = compile(rules, &mut fields, &(0..10));
+		assert_eq!(fields.len(), 9);
+		assert_eq!(
+			combinations(&tree, all_parts(9, &(0..10))),
+			3 * 10u128.pow(8)
+		);
+
+		let domain = -9_000_000_000_000_000_000..9_000_000_000_000_000_000;
+		let tree = compile("in{x<0:A,R}", &mut Interner::new(), &domain);
+		assert_eq!(
+			combinations(&tree, all_parts(2, &domain)),
+			9_000_000_000_000_000_000 * 18_000_000_000_000_000_000
+		);
+		let regions = tree.accepted_regions(all_parts(3, &domain));
+		assert_eq!(
+			count_combinations(&regions).unwrap_err().to_string(),
+			"Too many combinations to count"
+		);
+	}
 
-	fn from_str(s: &str) -> Result<Self, Self::Err> {
-		lazy_static::lazy_static! {
-			static ref PATTERN: Regex = Regex::new(r"^\{x=(\d+),m=(\d+),a=(\d+),s=(\d+)\}$").unwrap();
-		}
+	#[test]
+	fn test_parse_errors() {
+		let error = |rules: &str| {
+			format!(
+				"{:#}",
+				parse_rules(rules, &mut Interner::new(), &DEFAULT_DOMAIN).unwrap_err()
+			)
+		};
 
-		let captures = PATTERN
-			.captures(s)
-			.ok_or_else(|| anyhow::anyhow!("Invalid input"))?;
-		let x = captures.get(1).unwrap().as_str().parse().unwrap();
-		let m = captures.get(2).unwrap().as_str().parse().unwrap();
-		let a = captures.get(3).unwrap().as_str().parse().unwrap();
-		let s = captures.get(4).unwrap().as_str().parse().unwrap();
-		Ok(Self([x, m, a, s]))
+		assert_eq!(
+			error("in{x<10:A,m!20:R,A}"),
+			"Invalid rule 'm!20:R' in workflow 'in': \
+			Expected one of '<', '<=', '>', '>=' or '==' at column 2 of 'm!20:R'"
+		);
+		assert_eq!(
+			error("in{x<10:A,m>=:R,A}"),
+			"Invalid rule 'm>=:R' in workflow 'in': Expected a number at column 4 of 'm>=:R'"
+		);
+		assert_eq!(
+			error("in{x<10:nope,A}"),
+			"Invalid rule 'x<10:nope' in workflow 'in': Unknown workflow 'nope'"
+		);
+		assert_eq!(
+			error("in{A}\nout{A"),
+			"Invalid workflow on line 2: Expected '}' at column 5 of 'out{A'"
+		);
+		assert_eq!(error("out{A}"), "Expected a start workflow");
+
+		let workflows = parse_rules(
+			"in{x<10:a,A}\na{b}\nb{m>5:in,R}",
+			&mut Interner::new(),
+			&DEFAULT_DOMAIN,
+		)
+		.unwrap();
+		assert_eq!(
+			DecisionTree::compile(&workflows).unwrap_err().to_string(),
+			"The workflows loop forever: in -> a -> b -> in"
+		);
+
+		let workflows =
+			parse_rules("in{x<10:A,x>5:in,R}", &mut Interner::new(), &DEFAULT_DOMAIN).unwrap();
+		assert_eq!(
+			DecisionTree::compile(&workflows).unwrap_err().to_string(),
+			"The workflows loop forever: in -> in"
+		);
+
+		let mut fields = Interner::new();
+		parse_rules("in{x<10:A,R}", &mut fields, &DEFAULT_DOMAIN).unwrap();
+		let error = parse_parts("{x=1}\n{y=2}", &mut fields, &DEFAULT_DOMAIN).unwrap_err();
+		assert_eq!(format!("{error:#}"), "Part '{x=1}' has no value for 'y'");
 	}
 }
@@ -0,0 +1,45 @@
+use std::io::{stdin, Read};
+
+use aoc2023::{
+	math,
+	pulse::{Network, PulseCount},
+};
+use itertools::Itertools;
+
+fn main() {
+	let input = {
+		let mut buf = String::new();
+		stdin().read_to_string(&mut buf).unwrap();
+		buf
+	};
+
+	let network: Network = input.parse().unwrap();
+
+	{
+		let mut network = network.clone();
+		let PulseCount { low, high } = (0..1000)
+			.map(|_| network.press_button(|_| {}))
+			.fold(PulseCount::default(), |acc, it| acc + it);
+
+		println!("Part 1: {}", low * high);
+	}
+
+	{
+		let mut network = network.clone();
+
+		/* `rx` is fed by a single conjunction, which only sends a low pulse when all of its inputs
+		 * last sent a high pulse. Those inputs send high pulses in independent cycles, so `rx`
+		 * receives a low pulse when all of these cycles line up. */
+		let rx = network.index_of("rx").expect("Expected an 'rx' module");
+		let conjunction = network
+			.inputs(rx)
+			.iter()
+			.copied()
+			.exactly_one()
+			.expect("Expected 'rx' to have a single input");
+		let cycle_inputs = network.inputs(conjunction).to_vec();
+
+		let cycles = network.first_high_pulses(&cycle_inputs);
+		println!("Part 2: {}", math::lcm_all(cycles));
+	}
+}
@@ -0,0 +1,154 @@
+use std::{
+	collections::{HashSet, VecDeque},
+	io::stdin,
+};
+
+use aoc2023::{
+	grid2d::{Grid2D, Grid2DGet as _},
+	math,
+	vector2d::Vector2D,
+};
+
+const DIRECTIONS: [Vector2D; 4] = [
+	Vector2D(0, -1),
+	Vector2D(1, 0),
+	Vector2D(0, 1),
+	Vector2D(-1, 0),
+];
+
+const STEPS_PART_1: usize = 64;
+const STEPS_PART_2: usize = 26501365;
+
+fn main() {
+	let input: Grid2D<u8> = stdin()
+		.lines()
+		.map_while(Result::ok)
+		.map(|line| line.bytes().collect::<Vec<_>>())
+		.collect();
+
+	let start = find_start(&input);
+
+	{
+		let histogram = distance_histogram(&input, start, STEPS_PART_1, false);
+		println!("Part 1: {}", reachable_in(&histogram, STEPS_PART_1));
+	}
+
+	{
+		/* Simulating this many steps is infeasible, but the input has an empty row and column
+		 * through the start, and an empty border. Because of that, the reachable area grows by the
+		 * same amount of map copies every time the step count increases by the size of the map, so
+		 * the amount of reachable plots is a quadratic function of the amount of map copies
+		 * crossed. Sampling it three times is enough to extrapolate to the requested step count. */
+		let size = input.width();
+		assert_eq!(size, input.height(), "Expected a square map");
+
+		let remainder = STEPS_PART_2 % size;
+		let samples = [0, 1, 2].map(|copies| remainder + copies * size);
+
+		let histogram = distance_histogram(&input, start, samples[2], true);
+		let values = samples.map(|steps| reachable_in(&histogram, steps) as i64);
+
+		let copies = (STEPS_PART_2 / size) as i64;
+		println!("Part 2: {}", math::extrapolate(&values, copies));
+	}
+}
+
+fn find_start(input: &Grid2D<u8>) -> Vector2D {
+	input
+		.enumerate()
+		.find_map(|(index, symbol)| (*symbol == b'S').then_some(index))
+		.expect("Start position in input")
+		.into()
+}
+
+/// Walks over the map breadth-first, and counts how many plots are first reached at each
+/// distance from the start, up to `max_steps`. If `tiled` is set, the map repeats infinitely in
+/// every direction.
+fn distance_histogram(
+	input: &Grid2D<u8>,
+	start: Vector2D,
+	max_steps: usize,
+	tiled: bool,
+) -> Vec<usize> {
+	let (width, height) = (input.width() as isize, input.height() as isize);
+	let is_garden_plot = |Vector2D(x, y): Vector2D| {
+		let position = if tiled {
+			Vector2D(x.rem_euclid(width), y.rem_euclid(height))
+		} else {
+			Vector2D(x, y)
+		};
+		input.get(position).is_some_and(|&symbol| symbol != b'#')
+	};
+
+	let mut histogram = vec![0; max_steps + 1];
+	let mut seen = HashSet::from([start]);
+	let mut queue = VecDeque::from([(start, 0)]);
+
+	while let Some((position, distance)) = queue.pop_front() {
+		histogram[distance] += 1;
+
+		if distance == max_steps {
+			continue;
+		}
+
+		for direction in DIRECTIONS {
+			let next = position + direction;
+			if is_garden_plot(next) && seen.insert(next) {
+				queue.push_back((next, distance + 1));
+			}
+		}
+	}
+
+	histogram
+}
+
+/// A plot can be reached in exactly `steps` steps if it can be reached in fewer steps, with an
+/// even amount of steps remaining. Those can be spent by stepping back and forth.
+fn reachable_in(histogram: &[usize], steps: usize) -> usize {
+	histogram[..=steps]
+		.iter()
+		.enumerate()
+		.filter(|(distance, _)| distance % 2 == steps % 2)
+		.map(|(_, count)| count)
+		.sum()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const EXAMPLE: &str = "\
+...........
+.....###.#.
+.###.##..#.
+..#.#...#..
+....#.#....
+.##..S####.
+.##..#...#.
+.......##..
+.##.#.####.
+.##..##.##.
+...........";
+
+	fn parse(input: &str) -> Grid2D<u8> {
+		input.lines().map(|line| line.bytes()).collect()
+	}
+
+	#[test]
+	fn test_reachable_in() {
+		let input = parse(EXAMPLE);
+		let histogram = distance_histogram(&input, find_start(&input), 6, false);
+		assert_eq!(reachable_in(&histogram, 6), 16);
+	}
+
+	#[test]
+	fn test_reachable_in_tiled() {
+		let input = parse(EXAMPLE);
+		let histogram = distance_histogram(&input, find_start(&input), 100, true);
+
+		assert_eq!(reachable_in(&histogram, 6), 16);
+		assert_eq!(reachable_in(&histogram, 10), 50);
+		assert_eq!(reachable_in(&histogram, 50), 1594);
+		assert_eq!(reachable_in(&histogram, 100), 6536);
+	}
+}
@@ -1,66 +1,104 @@
 use std::{io::stdin, ops::Add, str::FromStr};
 
+use aoc2023::{graph::Graph, grid2d::Grid2D, parse};
+use itertools::Itertools;
+
 fn main() {
-	let mut bricks = stdin()
+	let bricks = stdin()
 		.lines()
-		.flatten()
+		.map_while(Result::ok)
 		.map(|line| Brick::from_str(&line).unwrap())
 		.collect::<Vec<_>>();
-	bricks.sort_by_key(|b| b.bottom());
-
-	fall_all(&mut bricks);
-	bricks.sort_by_key(|b| b.top());
 
-	let (part1, part2) = (0..bricks.len())
-		.map(|i| {
-			let mut bricks = bricks.clone();
-			bricks.remove(i);
+	let supports = settle(bricks);
+	let chain_reactions = chain_reaction_sizes(&supports);
 
-			fall_all(&mut bricks)
-		})
-		.fold((0, 0), |(mut count, mut sum), it| {
-			if it == 0 {
-				count += 1;
-			}
-			sum += it;
-			(count, sum)
-		});
+	let part1 = chain_reactions.iter().filter(|&&size| size == 0).count();
 	println!("Part 1: {part1}");
+
+	let part2 = chain_reactions.iter().sum::<usize>();
 	println!("Part 2: {part2}");
 }
 
-fn can_fall_for(brick: Brick, bricks: &[Brick]) -> i64 {
-	bricks
-		.iter()
-		.rev()
-		.filter_map(|b| {
-			let distance = brick.bottom() - b.top();
-			let brick = brick + Vector3D::from_z(-distance);
-			b.collides_with(&brick).then_some(distance - 1)
-		})
-		.min()
-		.unwrap_or(brick.bottom() - 1)
-}
+/// Which bricks rest on which other bricks, once they've all settled. Node 0 is the ground, which
+/// has no brick; every other node is a settled brick, with edges to the bricks it supports.
+type SupportGraph = Graph<Option<Brick>, ()>;
+
+const GROUND: usize = 0;
 
-fn fall(bricks: &mut [Brick], index: usize) -> bool {
-	let distance = can_fall_for(bricks[index], &bricks[..index]);
+/// Lets all bricks fall down until they rest on the ground or on another brick.
+///
+/// The bricks are dropped from the lowest to the highest, while keeping track of the highest brick
+/// on every column. A brick lands right on top of the highest column below it, and is supported by
+/// every brick that reaches that height. The bricks are added to the graph in that order, so every
+/// edge points to a higher index.
+fn settle(mut bricks: Vec<Brick>) -> SupportGraph {
+	bricks.sort_by_key(|b| b.bottom());
+
+	let width = bricks
+		.iter()
+		.map(|b| b.rear_top_right.x + 1)
+		.max()
+		.unwrap_or(0);
+	let depth = bricks
+		.iter()
+		.map(|b| b.rear_top_right.y + 1)
+		.max()
+		.unwrap_or(0);
+
+	// The height and the node of the highest brick in every column
+	let mut height_map: Grid2D<(i64, usize)> = Grid2D::with_size(width as usize, depth as usize);
+
+	let mut supports = Graph::new();
+	supports.add_node(None);
+
+	for brick in bricks {
+		let columns = brick.columns().collect::<Vec<_>>();
+		let resting_height = columns
+			.iter()
+			.map(|&column| height_map[column].0)
+			.max()
+			.unwrap_or(0);
+
+		let brick = brick + Vector3D::from_z(resting_height + 1 - brick.bottom());
+		let node = supports.add_node(Some(brick));
+
+		let supporters = columns
+			.iter()
+			.map(|&column| height_map[column])
+			.filter(|&(height, _)| height == resting_height)
+			.map(|(_, supporter)| supporter)
+			.unique()
+			.collect::<Vec<_>>();
+		for supporter in supporters {
+			supports.add_edge(supporter, node, ());
+		}
 
-	if distance > 0 {
-		bricks[index] = bricks[index] + Vector3D::from_z(-distance);
-		true
-	} else {
-		false
+		for column in columns {
+			height_map[column] = (brick.top(), node);
+		}
 	}
+
+	supports
 }
 
-fn fall_all(bricks: &mut [Brick]) -> usize {
-	let mut count = 0;
-	for i in 0..bricks.len() {
-		if fall(bricks, i) {
-			count += 1;
+/// For every brick, the amount of other bricks that would fall if it was disintegrated.
+///
+/// A brick falls when every path through the supports down to the ground is broken, so exactly the
+/// bricks that the disintegrated brick dominates fall.
+fn chain_reaction_sizes(supports: &SupportGraph) -> Vec<usize> {
+	let dominators = supports.dominators(GROUND);
+
+	/* Count the size of every subtree of the dominator tree. A brick's dominator always has a
+	 * lower index, so going over them from high to low sees every brick before its dominator. */
+	let mut dominated = vec![0; supports.node_count()];
+	for node in (1..supports.node_count()).rev() {
+		if let Some(dominator) = dominators[node] {
+			dominated[dominator] += dominated[node] + 1;
 		}
 	}
-	count
+
+	dominated[1..].to_vec()
 }
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
@@ -92,17 +130,8 @@ impl FromStr for Vector3D {
 	type Err = anyhow::Error;
 
 	fn from_str(s: &str) -> Result<Self, Self::Err> {
-		let mut iter = s.split(',').map(|s| s.parse::<i64>());
-		let mut next = || {
-			iter.next()
-				.ok_or_else(|| anyhow::anyhow!("Not enough components"))
-		};
-
-		Ok(Self {
-			x: next()??,
-			y: next()??,
-			z: next()??,
-		})
+		let [x, y, z] = parse::ints(s)?;
+		Ok(Self { x, y, z })
 	}
 }
 
@@ -121,13 +150,10 @@ impl Brick {
 		self.front_bottom_left.z
 	}
 
-	fn collides_with(&self, other: &Brick) -> bool {
-		!(self.front_bottom_left.x > other.rear_top_right.x
-			|| self.rear_top_right.x < other.front_bottom_left.x
-			|| self.front_bottom_left.y > other.rear_top_right.y
-			|| self.rear_top_right.y < other.front_bottom_left.y
-			|| self.front_bottom_left.z > other.rear_top_right.z
-			|| self.rear_top_right.z < other.front_bottom_left.z)
+	/// The `(x, y)` positions of all cubes in the brick, seen from above.
+	fn columns(&self) -> impl Iterator<Item = (usize, usize)> {
+		let (from, to) = (self.front_bottom_left, self.rear_top_right);
+		(from.y..=to.y).flat_map(move |y| (from.x..=to.x).map(move |x| (x as usize, y as usize)))
 	}
 }
 
@@ -155,3 +181,44 @@ impl std::ops::Add<Vector3D> for Brick {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const EXAMPLE: &str = "\
+1,0,1~1,2,1
+0,0,2~2,0,2
+0,2,3~2,2,3
+0,0,4~0,2,4
+2,0,5~2,2,5
+0,1,6~2,1,6
+1,1,8~1,1,9";
+
+	#[test]
+	fn test_settle() {
+		let bricks = EXAMPLE.lines().map(|l| l.parse().unwrap()).collect();
+		let supports = settle(bricks);
+
+		let bottoms = (1..supports.node_count())
+			.map(|node| supports.node(node).unwrap().bottom())
+			.collect::<Vec<_>>();
+		assert_eq!(bottoms, vec![1, 2, 2, 3, 3, 4, 5]);
+
+		// Brick A supports both B and C, which both support D and E
+		let supported_by = |node| {
+			supports
+				.successors(node)
+				.map(|(n, _)| n)
+				.collect::<Vec<_>>()
+		};
+		assert_eq!(supported_by(1), vec![2, 3]);
+		assert_eq!(supported_by(2), vec![4, 5]);
+		assert_eq!(supported_by(3), vec![4, 5]);
+
+		let sizes = chain_reaction_sizes(&supports);
+		assert_eq!(sizes, vec![6, 0, 0, 0, 0, 1, 0]);
+		assert_eq!(sizes.iter().filter(|&&size| size == 0).count(), 5);
+		assert_eq!(sizes.iter().sum::<usize>(), 7);
+	}
+}
@@ -4,17 +4,20 @@ use std::{
 };
 
 use aoc2023::{
+	graph::Graph,
 	grid2d::{Grid2D, Grid2DGet},
 	vector2d::Vector2D,
 };
 
 type Map = Grid2D<Square>;
-type DistanceGraph = Vec<Vec<(usize, u64)>>;
+/// The junctions of the map, connected by the lengths of the paths between them. The start is the
+/// first node, and the destination the last one.
+type DistanceGraph = Graph<Vector2D, u64>;
 
 fn main() {
 	let input = stdin()
 		.lines()
-		.flatten()
+		.map_while(Result::ok)
 		.map(|line| {
 			line.bytes()
 				.map(|b| b.try_into().unwrap())
@@ -76,10 +79,16 @@ fn to_distance_graph(map: &Map) -> DistanceGraph {
 		connections
 	}
 
-	nodes
-		.iter()
-		.map(|&start| find_connected_nodes(map, &node_mapping, start))
-		.collect()
+	let mut graph = Graph::new();
+	for &node in &nodes {
+		graph.add_node(node);
+	}
+	for (i, &node) in nodes.iter().enumerate() {
+		for (j, distance) in find_connected_nodes(map, &node_mapping, node) {
+			graph.add_edge(i, j, distance);
+		}
+	}
+	graph
 }
 
 fn around(map: &Grid2D<Square>, position: Vector2D) -> impl Iterator<Item = Vector2D> + '_ {
@@ -124,16 +133,16 @@ fn find_longest_path(distances: &DistanceGraph) -> u64 {
 		distance: u64,
 		distances: &DistanceGraph,
 	) -> Option<u64> {
-		if start == distances.len() - 1 {
+		if start == distances.node_count() - 1 {
 			return Some(distance);
 		}
 
 		seen |= 1 << start;
 
-		distances[start]
-			.iter()
+		distances
+			.successors(start)
 			.filter(|(i, _)| seen & (1 << i) == 0)
-			.flat_map(|&(i, d)| find_paths(seen, i, distance + d, distances))
+			.flat_map(|(i, &d)| find_paths(seen, i, distance + d, distances))
 			.max()
 	}
 
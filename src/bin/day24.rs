@@ -0,0 +1,283 @@
+use std::{io::stdin, ops::RangeInclusive, str::FromStr};
+
+use aoc2023::{math, parse};
+use itertools::Itertools;
+
+const TEST_AREA: RangeInclusive<i64> = 200000000000000..=400000000000000;
+
+fn main() {
+	let hailstones = stdin()
+		.lines()
+		.map_while(Result::ok)
+		.map(|line| Hailstone::from_str(&line).unwrap())
+		.collect::<Vec<_>>();
+
+	println!("Part 1: {}", count_intersections(&hailstones, TEST_AREA));
+
+	let rock = find_rock(&hailstones).expect("Expected a rock that hits every hailstone");
+	println!("Part 2: {}", rock.position.iter().sum::<i64>());
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Hailstone {
+	position: [i64; 3],
+	velocity: [i64; 3],
+}
+
+impl FromStr for Hailstone {
+	type Err = anyhow::Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let [x, y, z, vx, vy, vz] = parse::ints(s)?;
+		Ok(Self {
+			position: [x, y, z],
+			velocity: [vx, vy, vz],
+		})
+	}
+}
+
+fn count_intersections(hailstones: &[Hailstone], area: RangeInclusive<i64>) -> usize {
+	hailstones
+		.iter()
+		.tuple_combinations()
+		.filter(|(h1, h2)| paths_cross_within(h1, h2, &area))
+		.count()
+}
+
+/// Whether the paths of both hailstones cross each other in the future, inside of the given area.
+/// Only the X and Y axes are considered.
+///
+/// The intersection point is a fraction, so instead of calculating it, all comparisons are done
+/// on the numerators, with the bounds multiplied by the denominator. That keeps everything exact.
+fn paths_cross_within(h1: &Hailstone, h2: &Hailstone, area: &RangeInclusive<i64>) -> bool {
+	let cross = |a: [i128; 2], b: [i128; 2]| a[0] * b[1] - a[1] * b[0];
+
+	let p1 = [h1.position[0] as i128, h1.position[1] as i128];
+	let v1 = [h1.velocity[0] as i128, h1.velocity[1] as i128];
+	let p2 = [h2.position[0] as i128, h2.position[1] as i128];
+	let v2 = [h2.velocity[0] as i128, h2.velocity[1] as i128];
+
+	let denominator = cross(v1, v2);
+	if denominator == 0 {
+		// Parallel paths
+		return false;
+	}
+
+	/* Solving `p1 + t1 * v1 = p2 + t2 * v2` gives `t1 = cross(d, v2) / cross(v1, v2)` and
+	 * `t2 = cross(d, v1) / cross(v1, v2)`. Flip the signs so the denominator is positive, which
+	 * keeps the direction of the comparisons intact. */
+	let sign = denominator.signum();
+	let denominator = denominator * sign;
+	let d = [p2[0] - p1[0], p2[1] - p1[1]];
+	let t1 = cross(d, v2) * sign;
+	let t2 = cross(d, v1) * sign;
+
+	if t1 < 0 || t2 < 0 {
+		// Crossed in the past
+		return false;
+	}
+
+	let (min, max) = (*area.start() as i128, *area.end() as i128);
+	(0..2).all(|axis| {
+		let position = p1[axis] * denominator + t1 * v1[axis];
+		min * denominator <= position && position <= max * denominator
+	})
+}
+
+/// Finds the rock that hits every hailstone, when thrown from a whole-numbered position with a
+/// whole-numbered velocity.
+///
+/// For the rock at `P` with velocity `V`, and every hailstone at `p` with velocity `v`, the paths
+/// have to meet: `(P - p) × (V - v) = 0`. On the XY plane, this expands to
+/// `P.x V.y - P.y V.x = P.x v.y - P.y v.x + p.x V.y - p.y V.x - p.x v.y + p.y v.x`. The left side
+/// is the same for every hailstone, so equating the right side of two hailstones gives a linear
+/// equation in `P.x`, `P.y`, `V.x` and `V.y`.
+///
+/// Solving for those four unknowns takes at least five hailstones, so `None` is returned for fewer.
+fn find_rock(hailstones: &[Hailstone]) -> Option<Hailstone> {
+	if hailstones.len() < 5 {
+		return None;
+	}
+
+	let equations = (1..5)
+		.map(|i| {
+			let [h1, h2] = [hailstones[0], hailstones[i]];
+			let ([x1, y1, _], [vx1, vy1, _]) = (h1.position, h1.velocity);
+			let ([x2, y2, _], [vx2, vy2, _]) = (h2.position, h2.velocity);
+
+			let coefficients = [vy1 - vy2, vx2 - vx1, y2 - y1, x1 - x2];
+			let constant = (x1 as i128 * vy1 as i128 - y1 as i128 * vx1 as i128)
+				- (x2 as i128 * vy2 as i128 - y2 as i128 * vx2 as i128);
+			(coefficients.map(|c| c as i128), constant)
+		})
+		.collect::<Vec<_>>();
+
+	let [x, y, vx, vy] = solve_linear_system(&equations)?;
+
+	/* With `P.x` and `V.x` known, the same equation on the XZ plane is linear in `P.z` and `V.z`:
+	 * `(P.x - p.x) V.z - (V.x - v.x) P.z = (P.x - p.x) v.z - (V.x - v.x) p.z`. Two hailstones are
+	 * enough to solve that with Cramer's rule. */
+	let [a, b] = [hailstones[0], hailstones[1]].map(|h| {
+		let dx = x as i128 - h.position[0] as i128;
+		let dvx = vx as i128 - h.velocity[0] as i128;
+		let constant = dx * h.velocity[2] as i128 - dvx * h.position[2] as i128;
+		(dx, -dvx, constant)
+	});
+
+	let determinant = a.0 * b.1 - a.1 * b.0;
+	if determinant == 0 {
+		return None;
+	}
+	let vz = (a.2 * b.1 - a.1 * b.2) / determinant;
+	let z = (a.0 * b.2 - a.2 * b.0) / determinant;
+
+	let rock = Hailstone {
+		position: [x, y, z as i64],
+		velocity: [vx, vy, vz as i64],
+	};
+
+	hailstones
+		.iter()
+		.all(|hailstone| hits(&rock, hailstone))
+		.then_some(rock)
+}
+
+/// Whether the rock and the hailstone are at the same position at some moment.
+fn hits(rock: &Hailstone, hailstone: &Hailstone) -> bool {
+	let mut time = None;
+
+	for axis in 0..3 {
+		let distance = hailstone.position[axis] as i128 - rock.position[axis] as i128;
+		let speed = rock.velocity[axis] as i128 - hailstone.velocity[axis] as i128;
+
+		if speed == 0 {
+			if distance != 0 {
+				return false;
+			}
+			continue;
+		}
+
+		if distance % speed != 0 {
+			return false;
+		}
+
+		let t = distance / speed;
+		if t < 0 || time.is_some_and(|time| time != t) {
+			return false;
+		}
+		time = Some(t);
+	}
+
+	true
+}
+
+/// Solves a system of 4 linear equations with integer coefficients, of which the solution is
+/// known to consist of integers.
+///
+/// Solving it directly with fractions would overflow even an `i128`, so it's solved modulo
+/// several large primes instead. The Chinese remainder theorem then combines those to the actual
+/// solution, as long as it lies within ±2^61.
+fn solve_linear_system(equations: &[([i128; 4], i128)]) -> Option<[i64; 4]> {
+	const PRIMES: [i64; 4] = [2147483647, 2147483629, 2147483587, 2147483579];
+
+	let solutions = PRIMES
+		.iter()
+		.filter_map(|&prime| Some((solve_modulo(equations, prime)?, prime)))
+		.take(2)
+		.collect::<Vec<_>>();
+
+	if solutions.len() < 2 {
+		return None;
+	}
+
+	let mut result = [0; 4];
+	for (i, value) in result.iter_mut().enumerate() {
+		let congruences = solutions
+			.iter()
+			.map(|(solution, prime)| (solution[i], *prime));
+		let (residue, modulus) = math::chinese_remainder(congruences)?;
+
+		// Values in the upper half of the range represent negative numbers
+		*value = if residue > modulus / 2 {
+			residue - modulus
+		} else {
+			residue
+		};
+	}
+
+	Some(result)
+}
+
+/// Gaussian elimination modulo a prime. Returns `None` if the system has no unique solution
+/// modulo this prime.
+fn solve_modulo(equations: &[([i128; 4], i128)], prime: i64) -> Option<[i64; 4]> {
+	let reduce = |n: i128| n.rem_euclid(prime as i128) as i64;
+	let inverse = |n: i64| math::extended_gcd(n, prime).1.rem_euclid(prime);
+
+	let mut rows = equations
+		.iter()
+		.map(|(coefficients, constant)| {
+			let mut row = [0; 5];
+			for (i, &c) in coefficients.iter().enumerate() {
+				row[i] = reduce(c);
+			}
+			row[4] = reduce(*constant);
+			row
+		})
+		.collect::<Vec<_>>();
+
+	for column in 0..4 {
+		let pivot = (column..rows.len()).find(|&row| rows[row][column] != 0)?;
+		rows.swap(column, pivot);
+
+		let pivot_inverse = inverse(rows[column][column]);
+		for value in rows[column].iter_mut() {
+			*value = *value * pivot_inverse % prime;
+		}
+
+		for row in 0..rows.len() {
+			if row == column || rows[row][column] == 0 {
+				continue;
+			}
+
+			let factor = rows[row][column];
+			let pivot_row = rows[column];
+			for (value, pivot_value) in rows[row].iter_mut().zip(pivot_row) {
+				*value = (*value - factor * pivot_value).rem_euclid(prime);
+			}
+		}
+	}
+
+	Some([0, 1, 2, 3].map(|i| rows[i][4]))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const EXAMPLE: &str = "\
+19, 13, 30 @ -2,  1, -2
+18, 19, 22 @ -1, -1, -2
+20, 25, 34 @ -2, -2, -4
+12, 31, 28 @ -1, -2, -1
+20, 19, 15 @  1, -5, -3";
+
+	fn parse(input: &str) -> Vec<Hailstone> {
+		input.lines().map(|line| line.parse().unwrap()).collect()
+	}
+
+	#[test]
+	fn test_count_intersections() {
+		assert_eq!(count_intersections(&parse(EXAMPLE), 7..=27), 2);
+	}
+
+	#[test]
+	fn test_find_rock() {
+		let rock = find_rock(&parse(EXAMPLE)).unwrap();
+		assert_eq!(rock.position, [24, 13, 10]);
+		assert_eq!(rock.velocity, [-3, 1, 2]);
+
+		assert_eq!(find_rock(&parse(EXAMPLE)[..4]), None);
+		assert_eq!(find_rock(&[]), None);
+	}
+}
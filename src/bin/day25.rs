@@ -0,0 +1,88 @@
+use std::{collections::HashMap, io::stdin};
+
+use aoc2023::mincut::stoer_wagner;
+
+fn main() {
+	let lines = stdin().lines().map_while(Result::ok).collect::<Vec<_>>();
+	let (names, edges) = parse_input(&lines);
+
+	let cut = stoer_wagner(names.len(), &edges).expect("Expected at least two components");
+	assert_eq!(cut.weight, 3, "Expected to cut exactly three wires");
+
+	for (a, b) in cut.cut_edges(&edges) {
+		eprintln!("Cut wire {}/{}", names[a], names[b]);
+	}
+
+	let (first, second) = cut.sizes();
+	println!("Part 1: {}", first * second);
+}
+
+/// Returns the component names, and the wires between them as edges of weight 1.
+fn parse_input(lines: &[String]) -> (Vec<&str>, Vec<(usize, usize, u64)>) {
+	let mut names = Vec::new();
+	let mut indices = HashMap::new();
+	let mut index_of = |name| {
+		*indices.entry(name).or_insert_with(|| {
+			names.push(name);
+			names.len() - 1
+		})
+	};
+
+	let mut edges = Vec::new();
+	for line in lines {
+		let (component, connections) = line.split_once(": ").expect("Malformed input");
+		let component = index_of(component);
+
+		for connection in connections.split_whitespace() {
+			edges.push((component, index_of(connection), 1));
+		}
+	}
+
+	(names, edges)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const EXAMPLE: &str = "\
+jqt: rhn xhk nvd
+rsh: frs pzl lsr
+xhk: hfx
+cmg: qnr nvd lhk bvb
+rhn: xhk bvb hfx
+bvb: xhk hfx
+pzl: lsr hfx nvd
+qnr: nvd
+ntq: jqt hfx bvb xhk
+nvd: lhk
+lsr: lhk
+rzs: qnr cmg lsr rsh
+frs: qnr lhk lsr";
+
+	#[test]
+	fn test_minimum_cut() {
+		let lines = EXAMPLE.lines().map(String::from).collect::<Vec<_>>();
+		let (names, edges) = parse_input(&lines);
+
+		let cut = stoer_wagner(names.len(), &edges).unwrap();
+		assert_eq!(cut.weight, 3);
+
+		let (first, second) = cut.sizes();
+		assert_eq!(first * second, 54);
+
+		let mut cut_wires = cut
+			.cut_edges(&edges)
+			.map(|(a, b)| {
+				let mut wire = [names[a], names[b]];
+				wire.sort();
+				wire
+			})
+			.collect::<Vec<_>>();
+		cut_wires.sort();
+		assert_eq!(
+			cut_wires,
+			vec![["bvb", "cmg"], ["hfx", "pzl"], ["jqt", "nvd"]]
+		);
+	}
+}
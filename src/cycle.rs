@@ -0,0 +1,103 @@
+/// Applies `step` to `state` `iterations` times, and returns the resulting state.
+///
+/// Once a state repeats, the steps have to follow the same cycle over and over again, so all
+/// complete cycles are skipped. The cycle is found with Brent's algorithm, which only keeps a
+/// single earlier state around and compares it as a whole, so no hashes are involved.
+///
+/// ```rust
+/// # use aoc2023::cycle::find_cycle;
+/// let step = |n: &mut u64| *n = (*n * *n + 1) % 1000;
+///
+/// let mut expected = 3;
+/// for _ in 0..10_000 {
+///     step(&mut expected);
+/// }
+///
+/// assert_eq!(find_cycle(3, 10_000, step), expected);
+/// assert_eq!(find_cycle(3, 0, step), 3);
+/// ```
+pub fn find_cycle<T>(mut state: T, iterations: usize, mut step: impl FnMut(&mut T)) -> T
+where
+	T: Eq + Clone,
+{
+	/* The state is compared with a checkpoint, which moves up to the current state every time
+	 * the distance between them reaches the next power of two. Once the distance exceeds the
+	 * cycle's length and the checkpoint lies in the cycle, the state comes back to it. */
+	let mut checkpoint = state.clone();
+	let mut distance = 0;
+	let mut limit = 1;
+
+	for i in 1..=iterations {
+		step(&mut state);
+		distance += 1;
+
+		if state == checkpoint {
+			/* The states repeat every `distance` steps from the checkpoint on */
+			for _ in 0..(iterations - i) % distance {
+				step(&mut state);
+			}
+			return state;
+		}
+
+		if distance == limit {
+			checkpoint = state.clone();
+			distance = 0;
+			limit *= 2;
+		}
+	}
+
+	state
+}
+
+#[cfg(test)]
+mod tests {
+	use std::hash::Hash;
+
+	use super::*;
+
+	/// A state of which every value has the same hash.
+	#[derive(Debug, Clone, PartialEq, Eq)]
+	struct Colliding(u32);
+
+	impl Hash for Colliding {
+		fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+			0.hash(state);
+		}
+	}
+
+	#[test]
+	fn test_hash_collisions() {
+		// Counts 0, 1, ..., 6, and then cycles through 3, 4, 5, 6
+		let step = |n: &mut Colliding| n.0 = if n.0 == 6 { 3 } else { n.0 + 1 };
+
+		for iterations in 0..50 {
+			let mut expected = Colliding(0);
+			for _ in 0..iterations {
+				step(&mut expected);
+			}
+			assert_eq!(find_cycle(Colliding(0), iterations, step), expected);
+		}
+		assert_eq!(find_cycle(Colliding(0), 1_000_000_000, step), Colliding(4));
+	}
+
+	#[test]
+	fn test_tails_and_lengths() {
+		for tail in 0..10 {
+			for length in 1..20 {
+				let step = |n: &mut u32| {
+					*n = if *n == tail + length - 1 {
+						tail
+					} else {
+						*n + 1
+					}
+				};
+
+				let mut expected = 0;
+				for iterations in 0..100 {
+					assert_eq!(find_cycle(0, iterations, step), expected);
+					step(&mut expected);
+				}
+			}
+		}
+	}
+}
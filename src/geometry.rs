@@ -0,0 +1,225 @@
+//! Polygons on the integer lattice. A polygon is a list of its vertices, in order; the last vertex
+//! connects back to the first one. The polygons should be simple, meaning their edges don't cross.
+//!
+//! Fewer than three vertices are treated as what they describe: nothing, a single point, or a line
+//! segment between two points. None of them have an area or an interior.
+
+use crate::{math, vector2d::Vector2D};
+
+/// Where a point lies relative to a polygon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PointLocation {
+	Inside,
+	Boundary,
+	Outside,
+}
+
+/// Twice the signed area of the polygon, calculated with the shoelace formula. Doubling it keeps
+/// the result whole. The sign depends on the orientation: it's positive when the vertices go
+/// counterclockwise with the Y-axis pointing up, which is clockwise with the Y-axis pointing down.
+///
+/// ```rust
+/// # use aoc2023::{geometry::double_signed_area, vector2d::Vector2D};
+/// let triangle = [Vector2D(0, 0), Vector2D(3, 0), Vector2D(0, 3)];
+/// assert_eq!(double_signed_area(&triangle), 9);
+///
+/// let reversed = [Vector2D(0, 3), Vector2D(3, 0), Vector2D(0, 0)];
+/// assert_eq!(double_signed_area(&reversed), -9);
+/// ```
+pub fn double_signed_area(vertices: &[Vector2D]) -> isize {
+	edges(vertices).map(|(a, b)| a.0 * b.1 - b.0 * a.1).sum()
+}
+
+/// The amount of lattice points on the edges of the polygon. For polygons of which every edge is
+/// horizontal or vertical, this is the same as the perimeter. A single point or a line segment
+/// counts every point on it once.
+///
+/// ```rust
+/// # use aoc2023::{geometry::boundary_points, vector2d::Vector2D};
+/// let rectangle = [Vector2D(0, 0), Vector2D(4, 0), Vector2D(4, 2), Vector2D(0, 2)];
+/// assert_eq!(boundary_points(&rectangle), 12);
+///
+/// let triangle = [Vector2D(0, 0), Vector2D(4, 2), Vector2D(0, 2)];
+/// assert_eq!(boundary_points(&triangle), 8);
+///
+/// assert_eq!(boundary_points(&[Vector2D(0, 0), Vector2D(3, 0)]), 4);
+/// assert_eq!(boundary_points(&[Vector2D(5, 5)]), 1);
+/// assert_eq!(boundary_points(&[]), 0);
+/// ```
+pub fn boundary_points(vertices: &[Vector2D]) -> usize {
+	let points_between = |a: Vector2D, b: Vector2D| {
+		math::gcd((b.0 - a.0).unsigned_abs(), (b.1 - a.1).unsigned_abs())
+	};
+
+	match vertices {
+		[] => 0,
+		[_] => 1,
+		/* Walking around a segment would count it twice, so it's walked once, up to and including
+		 * the far end */
+		&[a, b] => points_between(a, b) + 1,
+		_ => edges(vertices).map(|(a, b)| points_between(a, b)).sum(),
+	}
+}
+
+/// The amount of lattice points strictly inside the polygon, using Pick's theorem:
+/// `area = interior + boundary / 2 - 1`. Degenerate polygons without any area have no interior.
+///
+/// ```rust
+/// # use aoc2023::{geometry::interior_points, vector2d::Vector2D};
+/// let rectangle = [Vector2D(0, 0), Vector2D(4, 0), Vector2D(4, 2), Vector2D(0, 2)];
+/// assert_eq!(interior_points(&rectangle), 3);
+///
+/// let line = [Vector2D(0, 0), Vector2D(3, 0), Vector2D(1, 0)];
+/// assert_eq!(interior_points(&line), 0);
+/// ```
+pub fn interior_points(vertices: &[Vector2D]) -> usize {
+	if vertices.len() < 3 {
+		return 0;
+	}
+
+	let double_area = double_signed_area(vertices).unsigned_abs();
+	(double_area + 2).saturating_sub(boundary_points(vertices)) / 2
+}
+
+/// The amount of lattice points inside of or on the edges of the polygon.
+///
+/// ```rust
+/// # use aoc2023::{geometry::lattice_points, vector2d::Vector2D};
+/// let rectangle = [Vector2D(0, 0), Vector2D(4, 0), Vector2D(4, 2), Vector2D(0, 2)];
+/// assert_eq!(lattice_points(&rectangle), 15);
+///
+/// assert_eq!(lattice_points(&[Vector2D(0, 0), Vector2D(4, 2)]), 3);
+/// assert_eq!(lattice_points(&[Vector2D(5, 5)]), 1);
+/// ```
+pub fn lattice_points(vertices: &[Vector2D]) -> usize {
+	interior_points(vertices) + boundary_points(vertices)
+}
+
+/// Whether the point lies inside, outside or on the edges of the polygon.
+///
+/// ```rust
+/// # use aoc2023::{geometry::{locate_point, PointLocation}, vector2d::Vector2D};
+/// let triangle = [Vector2D(0, 0), Vector2D(4, 0), Vector2D(0, 4)];
+/// assert_eq!(locate_point(&triangle, Vector2D(1, 1)), PointLocation::Inside);
+/// assert_eq!(locate_point(&triangle, Vector2D(2, 2)), PointLocation::Boundary);
+/// assert_eq!(locate_point(&triangle, Vector2D(3, 3)), PointLocation::Outside);
+/// ```
+pub fn locate_point(vertices: &[Vector2D], point: Vector2D) -> PointLocation {
+	let mut inside = false;
+
+	for (a, b) in edges(vertices) {
+		let (edge_x, edge_y) = (b.0 - a.0, b.1 - a.1);
+		let (point_x, point_y) = (point.0 - a.0, point.1 - a.1);
+
+		let on_line = edge_x * point_y - edge_y * point_x == 0;
+		let within_edge = a.0.min(b.0) <= point.0
+			&& point.0 <= a.0.max(b.0)
+			&& a.1.min(b.1) <= point.1
+			&& point.1 <= a.1.max(b.1);
+		if on_line && within_edge {
+			return PointLocation::Boundary;
+		}
+
+		/* Cast a ray from the point towards positive X, and count the edges it crosses. The
+		 * comparison is the same as `point.x < crossing.x`, multiplied by `edge_y`. */
+		if (a.1 > point.1) != (b.1 > point.1) {
+			let crosses = if edge_y > 0 {
+				point_x * edge_y < point_y * edge_x
+			} else {
+				point_x * edge_y > point_y * edge_x
+			};
+			inside ^= crosses;
+		}
+	}
+
+	if inside {
+		PointLocation::Inside
+	} else {
+		PointLocation::Outside
+	}
+}
+
+/// Every edge of the polygon as a pair of vertices, including the one closing it.
+fn edges(vertices: &[Vector2D]) -> impl Iterator<Item = (Vector2D, Vector2D)> + '_ {
+	vertices
+		.iter()
+		.copied()
+		.zip(vertices.iter().copied().cycle().skip(1))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// A U-shape, traced clockwise with the Y-axis pointing down.
+	fn u_shape() -> Vec<Vector2D> {
+		[
+			(0, 0),
+			(2, 0),
+			(2, 3),
+			(4, 3),
+			(4, 0),
+			(6, 0),
+			(6, 5),
+			(0, 5),
+		]
+		.into_iter()
+		.map(|(x, y)| Vector2D(x, y))
+		.collect()
+	}
+
+	#[test]
+	fn test_counts() {
+		let polygon = u_shape();
+
+		assert_eq!(double_signed_area(&polygon), 2 * 24);
+		assert_eq!(boundary_points(&polygon), 28);
+		assert_eq!(interior_points(&polygon), 11);
+		assert_eq!(lattice_points(&polygon), 39);
+
+		let reversed = polygon.iter().rev().copied().collect::<Vec<_>>();
+		assert_eq!(double_signed_area(&reversed), -2 * 24);
+		assert_eq!(interior_points(&reversed), 11);
+	}
+
+	#[test]
+	fn test_locate_point() {
+		let polygon = u_shape();
+
+		let mut counts = [0; 3];
+		for y in -1..=6 {
+			for x in -1..=7 {
+				let location = locate_point(&polygon, Vector2D(x, y));
+				counts[location as usize] += 1;
+			}
+		}
+		assert_eq!(counts, [11, 28, 9 * 8 - 39]);
+
+		assert_eq!(
+			locate_point(&polygon, Vector2D(3, 1)),
+			PointLocation::Outside
+		);
+		assert_eq!(
+			locate_point(&polygon, Vector2D(3, 3)),
+			PointLocation::Boundary
+		);
+		assert_eq!(
+			locate_point(&polygon, Vector2D(3, 4)),
+			PointLocation::Inside
+		);
+	}
+
+	#[test]
+	fn test_degenerate() {
+		assert_eq!(double_signed_area(&[]), 0);
+		assert_eq!(interior_points(&[Vector2D(1, 1)]), 0);
+		assert_eq!(boundary_points(&[Vector2D(0, 0), Vector2D(3, 0)]), 4);
+		assert_eq!(lattice_points(&[Vector2D(0, 0), Vector2D(0, -3)]), 4);
+		assert_eq!(lattice_points(&[Vector2D(1, 1)]), 1);
+		assert_eq!(lattice_points(&[]), 0);
+
+		let collinear = [Vector2D(0, 0), Vector2D(3, 0), Vector2D(1, 0)];
+		assert_eq!(double_signed_area(&collinear), 0);
+		assert_eq!(interior_points(&collinear), 0);
+	}
+}
@@ -0,0 +1,463 @@
+use std::{collections::VecDeque, fmt::Write};
+
+/// A directed graph, with a label `N` on every node and a weight `E` on every edge. Nodes and
+/// edges are identified by the index in which they were added.
+///
+/// Both the outgoing and the incoming edges of every node are kept, so the graph can be walked in
+/// both directions.
+///
+/// ```rust
+/// # use aoc2023::graph::Graph;
+/// let mut graph = Graph::new();
+/// let a = graph.add_node("a");
+/// let b = graph.add_node("b");
+/// let c = graph.add_node("c");
+/// graph.add_edge(a, b, 1);
+/// graph.add_edge(a, c, 4);
+/// graph.add_edge(b, c, 2);
+///
+/// assert_eq!(graph.successors(a).collect::<Vec<_>>(), vec![(b, &1), (c, &4)]);
+/// assert_eq!(graph.predecessors(c).collect::<Vec<_>>(), vec![(a, &4), (b, &2)]);
+/// assert_eq!(graph.topological_sort(), Some(vec![a, b, c]));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Graph<N, E> {
+	nodes: Vec<N>,
+	edges: Vec<Edge<E>>,
+	outgoing: Vec<Vec<usize>>,
+	incoming: Vec<Vec<usize>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edge<E> {
+	pub from: usize,
+	pub to: usize,
+	pub weight: E,
+}
+
+impl<N, E> Graph<N, E> {
+	pub fn new() -> Self {
+		Self {
+			nodes: Vec::new(),
+			edges: Vec::new(),
+			outgoing: Vec::new(),
+			incoming: Vec::new(),
+		}
+	}
+
+	/// Adds a node, and returns its index.
+	pub fn add_node(&mut self, label: N) -> usize {
+		self.nodes.push(label);
+		self.outgoing.push(Vec::new());
+		self.incoming.push(Vec::new());
+		self.nodes.len() - 1
+	}
+
+	/// Adds an edge between two existing nodes, and returns its index.
+	pub fn add_edge(&mut self, from: usize, to: usize, weight: E) -> usize {
+		assert!(
+			from < self.nodes.len() && to < self.nodes.len(),
+			"Edge {from} -> {to} refers to a missing node"
+		);
+
+		self.edges.push(Edge { from, to, weight });
+		let index = self.edges.len() - 1;
+		self.outgoing[from].push(index);
+		self.incoming[to].push(index);
+		index
+	}
+
+	pub fn node_count(&self) -> usize {
+		self.nodes.len()
+	}
+
+	pub fn edge_count(&self) -> usize {
+		self.edges.len()
+	}
+
+	pub fn node(&self, index: usize) -> &N {
+		&self.nodes[index]
+	}
+
+	pub fn node_mut(&mut self, index: usize) -> &mut N {
+		&mut self.nodes[index]
+	}
+
+	/// The index of the first node with the given label.
+	pub fn find_node(&self, label: &N) -> Option<usize>
+	where
+		N: PartialEq,
+	{
+		self.nodes.iter().position(|n| n == label)
+	}
+
+	pub fn nodes(&self) -> impl Iterator<Item = (usize, &N)> {
+		self.nodes.iter().enumerate()
+	}
+
+	pub fn edges(&self) -> impl Iterator<Item = &Edge<E>> {
+		self.edges.iter()
+	}
+
+	/// The nodes that the node has an edge to, together with the weight of that edge.
+	pub fn successors(&self, node: usize) -> impl Iterator<Item = (usize, &E)> {
+		self.outgoing[node].iter().map(|&edge| {
+			let edge = &self.edges[edge];
+			(edge.to, &edge.weight)
+		})
+	}
+
+	/// The nodes that have an edge to the node, together with the weight of that edge.
+	pub fn predecessors(&self, node: usize) -> impl Iterator<Item = (usize, &E)> {
+		self.incoming[node].iter().map(|&edge| {
+			let edge = &self.edges[edge];
+			(edge.from, &edge.weight)
+		})
+	}
+
+	/// All nodes reachable from `start`, in breadth-first order.
+	pub fn breadth_first(&self, start: usize) -> Vec<usize> {
+		let mut seen = vec![false; self.nodes.len()];
+		seen[start] = true;
+
+		let mut order = Vec::new();
+		let mut queue = VecDeque::from([start]);
+
+		while let Some(node) = queue.pop_front() {
+			order.push(node);
+
+			for (next, _) in self.successors(node) {
+				if !seen[next] {
+					seen[next] = true;
+					queue.push_back(next);
+				}
+			}
+		}
+
+		order
+	}
+
+	/// All nodes reachable from `start`, in depth-first pre-order.
+	pub fn depth_first(&self, start: usize) -> Vec<usize> {
+		let mut seen = vec![false; self.nodes.len()];
+		let mut order = Vec::new();
+		let mut stack = vec![start];
+
+		while let Some(node) = stack.pop() {
+			if seen[node] {
+				continue;
+			}
+			seen[node] = true;
+			order.push(node);
+
+			/* Reversed, so the first successor is visited first */
+			let successors = self.successors(node).map(|(next, _)| next);
+			stack.extend(successors.collect::<Vec<_>>().into_iter().rev());
+		}
+
+		order
+	}
+
+	/// Orders the nodes so every edge points forward. Returns `None` if the graph contains a
+	/// cycle.
+	pub fn topological_sort(&self) -> Option<Vec<usize>> {
+		let mut in_degrees = self.incoming.iter().map(Vec::len).collect::<Vec<_>>();
+		let mut queue = (0..self.nodes.len())
+			.filter(|&node| in_degrees[node] == 0)
+			.collect::<VecDeque<_>>();
+		let mut order = Vec::with_capacity(self.nodes.len());
+
+		while let Some(node) = queue.pop_front() {
+			order.push(node);
+
+			for (next, _) in self.successors(node) {
+				in_degrees[next] -= 1;
+				if in_degrees[next] == 0 {
+					queue.push_back(next);
+				}
+			}
+		}
+
+		(order.len() == self.nodes.len()).then_some(order)
+	}
+
+	/// Groups the nodes in strongly connected components: sets of nodes that can all reach each
+	/// other. The components are returned in topological order.
+	///
+	/// ```rust
+	/// # use aoc2023::graph::Graph;
+	/// let mut graph = Graph::new();
+	/// for label in 0..4 {
+	///     graph.add_node(label);
+	/// }
+	/// graph.add_edge(0, 1, ());
+	/// graph.add_edge(1, 0, ());
+	/// graph.add_edge(1, 2, ());
+	/// graph.add_edge(3, 2, ());
+	///
+	/// assert_eq!(
+	///     graph.strongly_connected_components(),
+	///     vec![vec![3], vec![0, 1], vec![2]]
+	/// );
+	/// ```
+	pub fn strongly_connected_components(&self) -> Vec<Vec<usize>> {
+		/* Kosaraju's algorithm: order the nodes by when a depth-first search finishes them, and
+		 * then collect the nodes that can reach them, in reverse finishing order. */
+		let mut finished = Vec::with_capacity(self.nodes.len());
+		let mut seen = vec![false; self.nodes.len()];
+
+		for root in 0..self.nodes.len() {
+			if !seen[root] {
+				self.post_order(root, &mut seen, &mut finished);
+			}
+		}
+
+		let mut component_of = vec![None; self.nodes.len()];
+		let mut components = Vec::new();
+
+		for &root in finished.iter().rev() {
+			if component_of[root].is_some() {
+				continue;
+			}
+
+			let mut component = Vec::new();
+			let mut stack = vec![root];
+			component_of[root] = Some(components.len());
+
+			while let Some(node) = stack.pop() {
+				component.push(node);
+				for (previous, _) in self.predecessors(node) {
+					if component_of[previous].is_none() {
+						component_of[previous] = Some(components.len());
+						stack.push(previous);
+					}
+				}
+			}
+
+			component.sort_unstable();
+			components.push(component);
+		}
+
+		components
+	}
+
+	/// The immediate dominator of every node reachable from `root`: the last node that every path
+	/// from the root to it has to pass through. The root itself, and the nodes it can't reach, have
+	/// no dominator.
+	///
+	/// ```rust
+	/// # use aoc2023::graph::Graph;
+	/// // A diamond, 0 -> {1, 2} -> 3, with a tail 3 -> 4
+	/// let mut graph = Graph::new();
+	/// for label in 0..6 {
+	///     graph.add_node(label);
+	/// }
+	/// for (from, to) in [(0, 1), (0, 2), (1, 3), (2, 3), (3, 4)] {
+	///     graph.add_edge(from, to, ());
+	/// }
+	///
+	/// assert_eq!(
+	///     graph.dominators(0),
+	///     vec![None, Some(0), Some(0), Some(0), Some(3), None]
+	/// );
+	/// ```
+	pub fn dominators(&self, root: usize) -> Vec<Option<usize>> {
+		/* The iterative algorithm by Cooper, Harvey and Kennedy. The nodes are processed in reverse
+		 * post-order, and every node's dominator is the closest common dominator of all its
+		 * predecessors that have been processed. */
+		let mut post_order = Vec::with_capacity(self.nodes.len());
+		self.post_order(root, &mut vec![false; self.nodes.len()], &mut post_order);
+
+		let mut order = vec![usize::MAX; self.nodes.len()];
+		for (i, &node) in post_order.iter().enumerate() {
+			order[node] = i;
+		}
+
+		let mut dominators = vec![None; self.nodes.len()];
+		dominators[root] = Some(root);
+
+		let intersect = |dominators: &[Option<usize>], mut a: usize, mut b: usize| {
+			while a != b {
+				while order[a] < order[b] {
+					a = dominators[a].unwrap();
+				}
+				while order[b] < order[a] {
+					b = dominators[b].unwrap();
+				}
+			}
+			a
+		};
+
+		let mut changed = true;
+		while changed {
+			changed = false;
+
+			for &node in post_order.iter().rev().skip(1) {
+				let dominator = self
+					.predecessors(node)
+					.map(|(previous, _)| previous)
+					.filter(|&previous| dominators[previous].is_some())
+					.reduce(|a, b| intersect(&dominators, a, b));
+
+				if dominator.is_some() && dominators[node] != dominator {
+					dominators[node] = dominator;
+					changed = true;
+				}
+			}
+		}
+
+		dominators[root] = None;
+		dominators
+	}
+
+	/// Appends the unseen nodes reachable from `root` to `order`, in depth-first post-order, and
+	/// marks them as seen.
+	fn post_order(&self, root: usize, seen: &mut [bool], order: &mut Vec<usize>) {
+		seen[root] = true;
+
+		let mut stack = vec![(root, 0)];
+		while let Some((node, next_edge)) = stack.pop() {
+			if let Some(&edge) = self.outgoing[node].get(next_edge) {
+				stack.push((node, next_edge + 1));
+
+				let next = self.edges[edge].to;
+				if !seen[next] {
+					seen[next] = true;
+					stack.push((next, 0));
+				}
+			} else {
+				order.push(node);
+			}
+		}
+	}
+
+	/// Renders the graph in the Graphviz DOT format, labelling the nodes and edges with the given
+	/// functions.
+	///
+	/// ```rust
+	/// # use aoc2023::graph::Graph;
+	/// let mut graph = Graph::new();
+	/// let a = graph.add_node("start");
+	/// let b = graph.add_node("end");
+	/// graph.add_edge(a, b, 12);
+	///
+	/// assert_eq!(
+	///     graph.to_dot(|n| n.to_string(), |e| e.to_string()),
+	///     "digraph {\n    0 [label=\"start\"];\n    1 [label=\"end\"];\n    0 -> 1 [label=\"12\"];\n}\n"
+	/// );
+	/// ```
+	pub fn to_dot(
+		&self,
+		node_label: impl Fn(&N) -> String,
+		edge_label: impl Fn(&E) -> String,
+	) -> String {
+		let escape = |label: String| label.replace('\\', "\\\\").replace('"', "\\\"");
+
+		let mut dot = String::from("digraph {\n");
+		for (index, node) in self.nodes() {
+			let label = escape(node_label(node));
+			writeln!(dot, "    {index} [label=\"{label}\"];").unwrap();
+		}
+		for Edge { from, to, weight } in self.edges() {
+			let label = escape(edge_label(weight));
+			writeln!(dot, "    {from} -> {to} [label=\"{label}\"];").unwrap();
+		}
+		dot.push_str("}\n");
+		dot
+	}
+}
+
+impl<N, E> Default for Graph<N, E> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn graph(node_count: usize, edges: &[(usize, usize)]) -> Graph<usize, ()> {
+		let mut graph = Graph::new();
+		for node in 0..node_count {
+			graph.add_node(node);
+		}
+		for &(from, to) in edges {
+			graph.add_edge(from, to, ());
+		}
+		graph
+	}
+
+	#[test]
+	fn test_traversal() {
+		let graph = graph(6, &[(0, 1), (0, 2), (1, 3), (2, 3), (3, 4), (5, 0)]);
+
+		assert_eq!(graph.breadth_first(0), vec![0, 1, 2, 3, 4]);
+		assert_eq!(graph.depth_first(0), vec![0, 1, 3, 4, 2]);
+		assert_eq!(graph.breadth_first(4), vec![4]);
+	}
+
+	#[test]
+	fn test_topological_sort() {
+		let acyclic = graph(4, &[(2, 1), (1, 0), (3, 0)]);
+		assert_eq!(acyclic.topological_sort(), Some(vec![2, 3, 1, 0]));
+
+		let cyclic = graph(3, &[(0, 1), (1, 2), (2, 0)]);
+		assert_eq!(cyclic.topological_sort(), None);
+	}
+
+	#[test]
+	fn test_dominators() {
+		/* 0 -> 1 -> 2 -> 4 -> 5
+		 *      1 -> 3 -> 4
+		 *           3 -> 2
+		 *                     5 -> 1 */
+		let graph = graph(
+			7,
+			&[
+				(0, 1),
+				(1, 2),
+				(2, 4),
+				(4, 5),
+				(1, 3),
+				(3, 4),
+				(3, 2),
+				(5, 1),
+			],
+		);
+
+		assert_eq!(
+			graph.dominators(0),
+			vec![None, Some(0), Some(1), Some(1), Some(1), Some(4), None]
+		);
+		assert_eq!(
+			graph.dominators(3),
+			vec![None, Some(5), Some(3), None, Some(3), Some(4), None]
+		);
+	}
+
+	#[test]
+	fn test_strongly_connected_components() {
+		let graph = graph(
+			8,
+			&[
+				(0, 1),
+				(1, 2),
+				(2, 0),
+				(2, 3),
+				(3, 4),
+				(4, 5),
+				(5, 3),
+				(6, 5),
+				(6, 7),
+				(7, 6),
+			],
+		);
+
+		let components = graph.strongly_connected_components();
+		assert_eq!(components.len(), 3);
+		assert!(components.contains(&vec![0, 1, 2]));
+		assert!(components.contains(&vec![6, 7]));
+		assert_eq!(components.last(), Some(&vec![3, 4, 5]));
+	}
+}
@@ -0,0 +1,236 @@
+use std::ops::Range;
+
+use crate::range;
+
+/// The ranges of a box, one for every axis. An array fixes the amount of dimensions at compile
+/// time, a `Vec` allows any amount at runtime.
+pub trait Axes: AsRef<[Range<i64>]> + AsMut<[Range<i64>]> + Clone {}
+
+impl<A: AsRef<[Range<i64>]> + AsMut<[Range<i64>]> + Clone> Axes for A {}
+
+/// An axis-aligned box, described by a half-open range for every axis.
+///
+/// ```rust
+/// # use aoc2023::hyperbox::Hyperbox;
+/// let fixed = Hyperbox([0..2, 0..3]);
+/// let dynamic = Hyperbox(vec![0..2; 5]);
+/// assert_eq!(fixed.volume(), Some(6));
+/// assert_eq!(dynamic.volume(), Some(32));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Hyperbox<A: Axes>(pub A);
+
+impl<A: Axes> Hyperbox<A> {
+	/// ```rust
+	/// # use aoc2023::hyperbox::Hyperbox;
+	/// let hyperbox = Hyperbox([0..10, 0..10]);
+	///
+	/// let [left, right] = hyperbox.split(0, 4);
+	/// assert_eq!(left, Hyperbox([0..4, 0..10]));
+	/// assert_eq!(right, Hyperbox([4..10, 0..10]));
+	///
+	/// // Splitting outside of the box leaves one of the sides empty
+	/// let [left, right] = hyperbox.split(1, 20);
+	/// assert_eq!(left, hyperbox);
+	/// assert!(right.is_empty());
+	/// ```
+	pub fn split(&self, axis: usize, point: i64) -> [Self; 2] {
+		let (mut left, mut right) = (self.0.clone(), self.0.clone());
+		(left.as_mut()[axis], right.as_mut()[axis]) =
+			range::split_at(&self.0.as_ref()[axis], point);
+
+		[Self(left), Self(right)]
+	}
+
+	/// The amount of points in the box, or `None` if that doesn't fit in a `u128`.
+	///
+	/// ```rust
+	/// # use aoc2023::hyperbox::Hyperbox;
+	/// assert_eq!(Hyperbox([0..2, 0..3, 0..4]).volume(), Some(24));
+	/// assert_eq!(Hyperbox([-2..2, 0..0]).volume(), Some(0));
+	///
+	/// let huge = i64::MIN..i64::MAX;
+	/// assert_eq!(Hyperbox([huge.clone(), huge.clone()]).volume(), Some(u64::MAX as u128 * u64::MAX as u128));
+	/// assert_eq!(Hyperbox([huge.clone(), huge.clone(), huge]).volume(), None);
+	/// ```
+	pub fn volume(&self) -> Option<u128> {
+		self.axes()
+			.iter()
+			.try_fold(1u128, |volume, r| volume.checked_mul(range::len(r) as u128))
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.axes().iter().any(|r| r.is_empty())
+	}
+
+	pub fn contains(&self, point: &[i64]) -> bool {
+		self.axes().iter().zip(point).all(|(r, p)| r.contains(p))
+	}
+
+	/// The range of every axis.
+	pub fn axes(&self) -> &[Range<i64>] {
+		self.0.as_ref()
+	}
+
+	/// ```rust
+	/// # use aoc2023::hyperbox::Hyperbox;
+	/// let fst = Hyperbox([0..10, 0..10]);
+	/// let snd = Hyperbox([5..15, -5..5]);
+	/// assert_eq!(fst.intersection(&snd), Some(Hyperbox([5..10, 0..5])));
+	///
+	/// // Touching, but not overlapping
+	/// let trd = Hyperbox([10..20, 0..10]);
+	/// assert_eq!(fst.intersection(&trd), None);
+	/// ```
+	pub fn intersection(&self, other: &Self) -> Option<Self> {
+		let mut result = self.0.clone();
+
+		for (range, other) in result.as_mut().iter_mut().zip(other.axes()) {
+			*range = range::intersection(range, other)?;
+		}
+
+		Some(Self(result))
+	}
+
+	/// The parts of `self` that aren't in `other`, as up to two disjoint boxes per axis.
+	///
+	/// ```rust
+	/// # use aoc2023::hyperbox::Hyperbox;
+	/// let fst = Hyperbox([0..10, 0..10]);
+	/// let snd = Hyperbox([2..4, 2..4]);
+	///
+	/// let difference = fst.difference(&snd);
+	/// assert_eq!(difference.len(), 4);
+	/// assert_eq!(difference.iter().map(|b| b.volume().unwrap()).sum::<u128>(), 96);
+	/// ```
+	pub fn difference(&self, other: &Self) -> Vec<Self> {
+		if self.intersection(other).is_none() {
+			return vec![self.clone()];
+		}
+
+		/* Cut off the parts outside of `other` one axis at a time. What remains after every axis
+		 * has been handled is the intersection, which is discarded. */
+		let mut result = Vec::new();
+		let mut remaining = self.clone();
+
+		for (axis, range) in other.axes().iter().enumerate() {
+			let [before, rest] = remaining.split(axis, range.start);
+			let [rest, after] = rest.split(axis, range.end);
+
+			result.extend([before, after].into_iter().filter(|b| !b.is_empty()));
+			remaining = rest;
+		}
+
+		result
+	}
+}
+
+/// A set of points, stored as disjoint boxes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HyperboxSet<A: Axes> {
+	boxes: Vec<Hyperbox<A>>,
+}
+
+impl<A: Axes> HyperboxSet<A> {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// ```rust
+	/// # use aoc2023::hyperbox::{Hyperbox, HyperboxSet};
+	/// let mut set = HyperboxSet::new();
+	///
+	/// set.insert(Hyperbox([0..10, 0..10]));
+	/// assert_eq!(set.volume(), Some(100));
+	///
+	/// // Only the part that wasn't covered yet is added
+	/// set.insert(Hyperbox([5..15, 5..15]));
+	/// assert_eq!(set.volume(), Some(175));
+	///
+	/// // Already covered completely
+	/// set.insert(Hyperbox([2..8, 2..8]));
+	/// assert_eq!(set.volume(), Some(175));
+	/// ```
+	pub fn insert(&mut self, hyperbox: Hyperbox<A>) {
+		let mut pieces = vec![hyperbox];
+
+		for existing in &self.boxes {
+			pieces = pieces
+				.iter()
+				.flat_map(|piece| piece.difference(existing))
+				.collect();
+		}
+
+		self.boxes
+			.extend(pieces.into_iter().filter(|b| !b.is_empty()));
+	}
+
+	pub fn union(&self, other: &Self) -> Self {
+		let mut result = self.clone();
+		result.extend(other.iter().cloned());
+		result
+	}
+
+	pub fn contains(&self, point: &[i64]) -> bool {
+		self.boxes.iter().any(|b| b.contains(point))
+	}
+
+	/// The total amount of points covered by the set, or `None` if that doesn't fit in a `u128`.
+	pub fn volume(&self) -> Option<u128> {
+		self.boxes
+			.iter()
+			.try_fold(0u128, |volume, b| volume.checked_add(b.volume()?))
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.boxes.is_empty()
+	}
+
+	/// The disjoint boxes making up the set. These aren't necessarily the boxes that were
+	/// inserted.
+	pub fn iter(&self) -> impl Iterator<Item = &Hyperbox<A>> + '_ {
+		self.boxes.iter()
+	}
+}
+
+impl<A: Axes> Default for HyperboxSet<A> {
+	fn default() -> Self {
+		Self { boxes: Vec::new() }
+	}
+}
+
+impl<A: Axes> Extend<Hyperbox<A>> for HyperboxSet<A> {
+	fn extend<T: IntoIterator<Item = Hyperbox<A>>>(&mut self, iter: T) {
+		for hyperbox in iter {
+			self.insert(hyperbox);
+		}
+	}
+}
+
+impl<A: Axes> FromIterator<Hyperbox<A>> for HyperboxSet<A> {
+	fn from_iter<T: IntoIterator<Item = Hyperbox<A>>>(iter: T) -> Self {
+		let mut set = Self::default();
+		set.extend(iter);
+		set
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_union_of_overlapping_cubes() {
+		let cubes = (0..3).map(|i| Hyperbox([i..(i + 3), i..(i + 3), i..(i + 3)]));
+		let set = cubes.collect::<HyperboxSet<_>>();
+
+		/* Inclusion-exclusion: neighbouring cubes overlap by 8, the first and last cube overlap by
+		 * 1, which is also where all three of them overlap. */
+		assert_eq!(set.volume(), Some(3 * 27 - 8 - 8 - 1 + 1));
+
+		for point in [[0, 0, 0], [2, 2, 2], [4, 4, 4]] {
+			assert!(set.contains(&point));
+		}
+		assert!(!set.contains(&[0, 0, 4]));
+	}
+}
@@ -0,0 +1,87 @@
+use std::{collections::HashMap, ops::Index};
+
+/// Maps strings to dense `u32` ids, and back. Ids are handed out in the order in which the strings
+/// are first seen, starting at 0, so they can be used as indices into a `Vec`.
+///
+/// ```rust
+/// # use aoc2023::interner::Interner;
+/// let mut interner = Interner::new();
+/// let aaa = interner.intern("AAA");
+/// let bbb = interner.intern("BBB");
+///
+/// assert_eq!((aaa, bbb), (0, 1));
+/// assert_eq!(interner.intern("AAA"), aaa);
+/// assert_eq!(interner.get("BBB"), Some(bbb));
+/// assert_eq!(interner.get("CCC"), None);
+/// assert_eq!(&interner[bbb], "BBB");
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct Interner {
+	ids: HashMap<Box<str>, u32>,
+	strings: Vec<Box<str>>,
+}
+
+impl Interner {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// The id of the string, assigning it a new id if it hasn't been seen before.
+	pub fn intern(&mut self, string: &str) -> u32 {
+		if let Some(&id) = self.ids.get(string) {
+			return id;
+		}
+
+		let id = u32::try_from(self.strings.len()).expect("Too many strings to intern");
+		self.strings.push(string.into());
+		self.ids.insert(string.into(), id);
+		id
+	}
+
+	/// The id of the string, if it has been interned.
+	pub fn get(&self, string: &str) -> Option<u32> {
+		self.ids.get(string).copied()
+	}
+
+	/// The string belonging to the id, if it exists.
+	pub fn resolve(&self, id: u32) -> Option<&str> {
+		self.strings.get(id as usize).map(|s| &**s)
+	}
+
+	pub fn len(&self) -> usize {
+		self.strings.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.strings.is_empty()
+	}
+
+	/// All ids with their strings, in order of the ids.
+	pub fn iter(&self) -> impl Iterator<Item = (u32, &str)> {
+		(0..).zip(self.strings.iter().map(|s| &**s))
+	}
+}
+
+impl Index<u32> for Interner {
+	type Output = str;
+
+	fn index(&self, id: u32) -> &Self::Output {
+		&self.strings[id as usize]
+	}
+}
+
+impl<'a> FromIterator<&'a str> for Interner {
+	fn from_iter<T: IntoIterator<Item = &'a str>>(iter: T) -> Self {
+		let mut interner = Self::new();
+		interner.extend(iter);
+		interner
+	}
+}
+
+impl<'a> Extend<&'a str> for Interner {
+	fn extend<T: IntoIterator<Item = &'a str>>(&mut self, iter: T) {
+		for string in iter {
+			self.intern(string);
+		}
+	}
+}
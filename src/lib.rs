@@ -1,6 +1,19 @@
 pub mod bitset;
+pub mod cycle;
+pub mod geometry;
+pub mod graph;
 pub mod grid2d;
+pub mod hyperbox;
+pub mod interner;
+pub mod math;
+pub mod memo;
+pub mod mincut;
+pub mod nonogram;
+pub mod parse;
+pub mod pulse;
 pub mod range;
+pub mod rangemap;
+pub mod rangeset;
 pub mod vector2d;
 
 use grid2d::{Grid2D, Grid2DGet, Grid2DGetMut};
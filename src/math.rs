@@ -0,0 +1,337 @@
+use std::ops::{Add, Div, Mul, Rem, Sub};
+
+/// The integer types, so functions like [`gcd`] don't have to be written for each of them.
+pub trait Integer:
+	Copy
+	+ Ord
+	+ Add<Output = Self>
+	+ Sub<Output = Self>
+	+ Mul<Output = Self>
+	+ Div<Output = Self>
+	+ Rem<Output = Self>
+{
+	const ZERO: Self;
+	const ONE: Self;
+	const MAX: Self;
+
+	/// The unsigned type of the same size, which can hold the distance between any two values.
+	type Unsigned: Integer;
+
+	/// The distance between the two values, which never overflows.
+	fn abs_diff(self, other: Self) -> Self::Unsigned;
+}
+
+macro_rules! integer_impl {
+	($($type: ty => $unsigned: ty),+) => {
+		$(
+			impl Integer for $type {
+				const ZERO: Self = 0;
+				const ONE: Self = 1;
+				const MAX: Self = <$type>::MAX;
+
+				type Unsigned = $unsigned;
+
+				fn abs_diff(self, other: Self) -> $unsigned {
+					<$type>::abs_diff(self, other)
+				}
+			}
+		)*
+	};
+}
+
+integer_impl!(
+	u8 => u8, u16 => u16, u32 => u32, u64 => u64, u128 => u128, usize => usize,
+	i8 => u8, i16 => u16, i32 => u32, i64 => u64, i128 => u128, isize => usize
+);
+
+/// The greatest common divisor of two non-negative numbers.
+///
+/// ```rust
+/// # use aoc2023::math::gcd;
+/// assert_eq!(gcd(12, 18), 6);
+/// assert_eq!(gcd(18, 12), 6);
+/// assert_eq!(gcd(7u8, 13), 1);
+/// assert_eq!(gcd(0, 5), 5);
+/// ```
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+	if b == T::ZERO {
+		a
+	} else {
+		gcd(b, a % b)
+	}
+}
+
+/// The least common multiple of two non-negative numbers.
+///
+/// ```rust
+/// # use aoc2023::math::lcm;
+/// assert_eq!(lcm(4, 6), 12);
+/// assert_eq!(lcm(7usize, 13), 91);
+/// assert_eq!(lcm(0, 5), 0);
+/// ```
+pub fn lcm<T: Integer>(a: T, b: T) -> T {
+	if a == T::ZERO || b == T::ZERO {
+		return T::ZERO;
+	}
+
+	a / gcd(a, b) * b
+}
+
+/// ```rust
+/// # use aoc2023::math::gcd_all;
+/// assert_eq!(gcd_all([12, 18, 27]), 3);
+/// assert_eq!(gcd_all(Vec::<u32>::new()), 0);
+/// ```
+pub fn gcd_all<T: Integer>(numbers: impl IntoIterator<Item = T>) -> T {
+	numbers.into_iter().fold(T::ZERO, gcd)
+}
+
+/// ```rust
+/// # use aoc2023::math::lcm_all;
+/// assert_eq!(lcm_all([2, 3, 4, 5]), 60);
+/// assert_eq!(lcm_all(Vec::<u32>::new()), 1);
+/// ```
+pub fn lcm_all<T: Integer>(numbers: impl IntoIterator<Item = T>) -> T {
+	numbers.into_iter().fold(T::ONE, lcm)
+}
+
+/// Finds `(gcd, x, y)` such that `a * x + b * y == gcd`.
+///
+/// ```rust
+/// # use aoc2023::math::extended_gcd;
+/// let (gcd, x, y) = extended_gcd(240, 46);
+/// assert_eq!(gcd, 2);
+/// assert_eq!(240 * x + 46 * y, 2);
+/// ```
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+	let (gcd, x, y) = extended_gcd_i128(a as i128, b as i128);
+	(gcd as i64, x as i64, y as i64)
+}
+
+fn extended_gcd_i128(a: i128, b: i128) -> (i128, i128, i128) {
+	if b == 0 {
+		return (a, 1, 0);
+	}
+
+	let (gcd, x, y) = extended_gcd_i128(b, a % b);
+	(gcd, y, x - (a / b) * y)
+}
+
+/// Solves a system of congruences `x ≡ residue (mod modulus)` with the Chinese remainder theorem.
+/// The moduli don't need to be coprime.
+///
+/// Returns the smallest non-negative solution, together with the modulus of the combined
+/// congruence (the lcm of all moduli). Returns `None` if the congruences contradict each other, if
+/// a modulus isn't positive, or if the combined modulus doesn't fit in an `i64`.
+///
+/// ```rust
+/// # use aoc2023::math::chinese_remainder;
+/// assert_eq!(chinese_remainder([(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+///
+/// // Moduli that share a factor
+/// assert_eq!(chinese_remainder([(3, 4), (1, 6)]), Some((7, 12)));
+/// assert_eq!(chinese_remainder([(3, 4), (2, 6)]), None);
+///
+/// // The combined modulus would be larger than `i64::MAX`
+/// assert_eq!(chinese_remainder([(1, 4_000_000_007), (2, 3_000_000_019)]), None);
+///
+/// // Moduli that aren't positive
+/// assert_eq!(chinese_remainder([(2, 3), (0, 0)]), None);
+/// assert_eq!(chinese_remainder([(2, 3), (1, -5)]), None);
+/// ```
+pub fn chinese_remainder(congruences: impl IntoIterator<Item = (i64, i64)>) -> Option<(i64, i64)> {
+	let (mut residue, mut modulus) = (0i128, 1i128);
+
+	for (r, m) in congruences {
+		if m <= 0 {
+			return None;
+		}
+
+		let (r, m) = (r as i128, m as i128);
+		let (gcd, x, _) = extended_gcd_i128(modulus, m);
+
+		if (r - residue) % gcd != 0 {
+			return None;
+		}
+
+		/* `modulus * x ≡ gcd (mod m)`, so adding `modulus * x * (r - residue) / gcd` to the
+		 * current residue keeps it valid for the previous congruences, and solves the new one. */
+		let step = m / gcd;
+		let k = ((r - residue) / gcd % step) * (x % step) % step;
+
+		/* Both moduli fit in an `i64`, so their product can't overflow an `i128` */
+		let lcm = modulus * step;
+		if lcm > i64::MAX as i128 {
+			return None;
+		}
+		residue = (residue + modulus * k).rem_euclid(lcm);
+		modulus = lcm;
+	}
+
+	Some((residue as i64, modulus as i64))
+}
+
+/// The integer square root, rounded down.
+///
+/// ```rust
+/// # use aoc2023::math::isqrt;
+/// assert_eq!(isqrt(0), 0);
+/// assert_eq!(isqrt(15), 3);
+/// assert_eq!(isqrt(16), 4);
+/// assert_eq!(isqrt(u64::MAX), u32::MAX as u64);
+/// ```
+pub fn isqrt(n: u64) -> u64 {
+	isqrt_u128(n as u128) as u64
+}
+
+fn isqrt_u128(n: u128) -> u128 {
+	// Floats lose precision for large numbers, so only use them as an estimate
+	let mut root = (n as f64).sqrt() as u128;
+	let square_above = |root: u128| root.checked_mul(root).map_or(true, |square| square > n);
+
+	while square_above(root) {
+		root -= 1;
+	}
+	while !square_above(root + 1) {
+		root += 1;
+	}
+
+	root
+}
+
+/// Finds the real roots of `a * x² + b * x + c`, rounded outwards: the smaller root is rounded
+/// down, and the larger root is rounded up. Returns `None` if there are no real roots.
+///
+/// This makes it easy to find the integers for which the polynomial is strictly above or below
+/// zero, as those are the ones exclusively between the bounds.
+///
+/// ```rust
+/// # use aoc2023::math::quadratic_root_bounds;
+/// // x² - 7x + 9 has roots at ~1.70 and ~5.30
+/// assert_eq!(quadratic_root_bounds(1, -7, 9), Some((1, 6)));
+/// assert_eq!(quadratic_root_bounds(-1, 7, -9), Some((1, 6)));
+///
+/// // Exact roots at 10 and 20
+/// assert_eq!(quadratic_root_bounds(1, -30, 200), Some((10, 20)));
+///
+/// // No real roots
+/// assert_eq!(quadratic_root_bounds(1, 0, 1), None);
+/// ```
+pub fn quadratic_root_bounds(a: i64, b: i64, c: i64) -> Option<(i64, i64)> {
+	assert!(a != 0, "Not a quadratic polynomial");
+
+	// Negating the polynomial doesn't change the roots, so make sure the parabola opens upwards
+	let (a, b, c) = if a < 0 { (-a, -b, -c) } else { (a, b, c) };
+	let (a, b, c) = (a as i128, b as i128, c as i128);
+
+	let discriminant = b * b - 4 * a * c;
+	if discriminant < 0 {
+		return None;
+	}
+	let root = isqrt_u128(discriminant as u128) as i128;
+
+	/* The integer square root is only an approximation of the actual square root, so the
+	 * estimates are corrected by checking the value of the polynomial around them. */
+	let f = |x: i128| a * x * x + b * x + c;
+	let doubled_vertex = -b;
+
+	let mut low = (-b - root).div_euclid(2 * a);
+	while f(low) < 0 {
+		low -= 1;
+	}
+	while f(low + 1) >= 0 && 2 * a * (low + 1) <= doubled_vertex {
+		low += 1;
+	}
+
+	let mut high = -(b - root).div_euclid(2 * a);
+	while f(high) < 0 {
+		high += 1;
+	}
+	while f(high - 1) >= 0 && 2 * a * (high - 1) >= doubled_vertex {
+		high -= 1;
+	}
+
+	Some((low as i64, high as i64))
+}
+
+/// Given the values of a polynomial at `x = 0, 1, 2, ...`, calculates its value at `x`, using the
+/// lowest degree polynomial that goes through all the values.
+///
+/// The polynomial is evaluated in Newton's forward difference form, which only needs integer
+/// arithmetic.
+///
+/// ```rust
+/// # use aoc2023::math::extrapolate;
+/// let values = [1, 3, 6, 10, 15, 21];
+/// assert_eq!(extrapolate(&values, 6), 28);
+/// assert_eq!(extrapolate(&values, -1), 0);
+///
+/// // Interpolating returns the original values
+/// assert_eq!(extrapolate(&values, 3), 10);
+/// ```
+pub fn extrapolate(values: &[i64], x: i64) -> i64 {
+	let mut differences = values.iter().map(|&v| v as i128).collect::<Vec<_>>();
+
+	/* Turn `differences[k]` into the k-th forward difference at `x = 0`, by repeatedly replacing
+	 * the tail of the Vec with its differences. */
+	for k in 1..differences.len() {
+		for i in (k..differences.len()).rev() {
+			differences[i] -= differences[i - 1];
+		}
+	}
+
+	// p(x) = Σ Δᵏ · C(x, k), where the binomial coefficient is also defined for negative `x`
+	let x = x as i128;
+	let mut binomial = 1i128;
+	let mut result = 0i128;
+
+	for (k, difference) in differences.into_iter().enumerate() {
+		result += difference * binomial;
+		binomial = binomial * (x - k as i128) / (k as i128 + 1);
+	}
+
+	result as i64
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_quadratic_root_bounds_large_values() {
+		let (time, distance) = (56_977_793i64, 499_221_010_971_440i64);
+		let (low, high) = quadratic_root_bounds(-1, time, -distance).unwrap();
+
+		let beats_distance = |hold: i64| hold * (time - hold) > distance;
+		assert!(!beats_distance(low));
+		assert!(beats_distance(low + 1));
+		assert!(beats_distance(high - 1));
+		assert!(!beats_distance(high));
+	}
+
+	#[test]
+	fn test_quadratic_root_bounds_huge_discriminant() {
+		// The discriminant is about 1e20, which doesn't fit in a u64
+		let b = -10_000_000_000i64;
+		assert_eq!(quadratic_root_bounds(1, b, 1), Some((0, 10_000_000_000)));
+		assert_eq!(quadratic_root_bounds(1, b, -1), Some((-1, 10_000_000_001)));
+	}
+
+	#[test]
+	fn test_chinese_remainder_large_moduli() {
+		let (m1, m2) = (3_000_000_019i64, 3_000_000_017i64);
+		let (residue, modulus) = chinese_remainder([(5, m1), (7, m2)]).unwrap();
+		assert_eq!(modulus, m1 * m2);
+		assert_eq!(residue % m1, 5);
+		assert_eq!(residue % m2, 7);
+
+		assert_eq!(chinese_remainder([(0, m1), (0, m2), (0, 2)]), None);
+	}
+
+	#[test]
+	fn test_extrapolate_backwards() {
+		let values = [10, 13, 16, 21, 30, 45];
+		assert_eq!(extrapolate(&values, 6), 68);
+		assert_eq!(extrapolate(&values, -1), 5);
+	}
+}
@@ -0,0 +1,68 @@
+use std::{collections::HashMap, hash::Hash};
+
+/// Caches the results of a recursive function. The function receives the `Memo` itself, and
+/// recurses by calling [`Memo::get`] on it, so every key is only ever calculated once.
+///
+/// ```rust
+/// # use aoc2023::memo::Memo;
+/// let fibonacci = |memo: &mut Memo<u64, u64>, n: u64| {
+///     if n < 2 {
+///         n
+///     } else {
+///         memo.get(n - 1) + memo.get(n - 2)
+///     }
+/// };
+///
+/// let mut memo = Memo::new(&fibonacci);
+/// assert_eq!(memo.get(90), 2880067194370816120);
+/// ```
+pub struct Memo<'a, K, V> {
+	cache: HashMap<K, V>,
+	function: &'a dyn Fn(&mut Memo<'a, K, V>, K) -> V,
+}
+
+impl<'a, K, V> Memo<'a, K, V>
+where
+	K: Hash + Eq + Clone,
+	V: Clone,
+{
+	pub fn new(function: &'a dyn Fn(&mut Memo<'a, K, V>, K) -> V) -> Self {
+		Self {
+			cache: HashMap::new(),
+			function,
+		}
+	}
+
+	pub fn get(&mut self, key: K) -> V {
+		if let Some(value) = self.cache.get(&key) {
+			return value.clone();
+		}
+
+		let function = self.function;
+		let value = function(self, key.clone());
+		self.cache.insert(key, value.clone());
+		value
+	}
+}
+
+/// Calculates a single value of a recursive function with a fresh [`Memo`].
+///
+/// ```rust
+/// # use aoc2023::memo::{memoize, Memo};
+/// // The amount of paths through a grid, only moving right or down
+/// let paths = memoize((16, 16), &|memo: &mut Memo<_, u64>, (x, y): (u32, u32)| {
+///     if x == 0 || y == 0 {
+///         1
+///     } else {
+///         memo.get((x - 1, y)) + memo.get((x, y - 1))
+///     }
+/// });
+/// assert_eq!(paths, 601080390);
+/// ```
+pub fn memoize<'a, K, V>(key: K, function: &'a dyn Fn(&mut Memo<'a, K, V>, K) -> V) -> V
+where
+	K: Hash + Eq + Clone,
+	V: Clone,
+{
+	Memo::new(function).get(key)
+}
@@ -0,0 +1,137 @@
+use std::collections::{BinaryHeap, HashMap};
+
+/// A minimum cut of an undirected graph, splitting its nodes in two sides.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinCut {
+	/// The total weight of the edges crossing the cut.
+	pub weight: u64,
+	/// For every node, whether it ended up on the first side of the cut.
+	pub side: Vec<bool>,
+}
+
+impl MinCut {
+	/// The amount of nodes on either side of the cut.
+	pub fn sizes(&self) -> (usize, usize) {
+		let first = self.side.iter().filter(|&&s| s).count();
+		(first, self.side.len() - first)
+	}
+
+	/// The edges that connect both sides of the cut.
+	pub fn cut_edges<'a>(
+		&'a self,
+		edges: &'a [(usize, usize, u64)],
+	) -> impl Iterator<Item = (usize, usize)> + 'a {
+		edges
+			.iter()
+			.filter(|(a, b, _)| self.side[*a] != self.side[*b])
+			.map(|&(a, b, _)| (a, b))
+	}
+}
+
+/// Finds a minimum cut of an undirected, weighted graph with the Stoer–Wagner algorithm. Nodes are
+/// numbered `0..node_count`, and `edges` contains `(node, node, weight)` triples.
+///
+/// Returns `None` if the graph has fewer than 2 nodes, as it can't be cut then.
+///
+/// ```rust
+/// # use aoc2023::mincut::stoer_wagner;
+/// // Two triangles, connected by a single edge
+/// let edges = [(0, 1, 1), (1, 2, 1), (2, 0, 1), (3, 4, 1), (4, 5, 1), (5, 3, 1), (2, 3, 1)];
+///
+/// let cut = stoer_wagner(6, &edges).unwrap();
+/// assert_eq!(cut.weight, 1);
+/// assert_eq!(cut.sizes(), (3, 3));
+/// assert_eq!(cut.cut_edges(&edges).collect::<Vec<_>>(), vec![(2, 3)]);
+/// ```
+pub fn stoer_wagner(node_count: usize, edges: &[(usize, usize, u64)]) -> Option<MinCut> {
+	if node_count < 2 {
+		return None;
+	}
+
+	/* Nodes get merged together during the algorithm. The merged node keeps the index of one of
+	 * the original nodes, and keeps track of all the original nodes it contains. */
+	let mut adjacency: Vec<HashMap<usize, u64>> = vec![HashMap::new(); node_count];
+	for &(a, b, weight) in edges {
+		if a != b {
+			*adjacency[a].entry(b).or_default() += weight;
+			*adjacency[b].entry(a).or_default() += weight;
+		}
+	}
+	let mut members: Vec<Vec<usize>> = (0..node_count).map(|i| vec![i]).collect();
+	let mut active: Vec<usize> = (0..node_count).collect();
+
+	let mut best: Option<(u64, Vec<usize>)> = None;
+
+	while active.len() > 1 {
+		let (cut_weight, second_last, last) = minimum_cut_phase(&adjacency, &active, node_count);
+
+		if best
+			.as_ref()
+			.map_or(true, |(weight, _)| cut_weight < *weight)
+		{
+			best = Some((cut_weight, members[last].clone()));
+		}
+
+		// Merge the last node into the one added before it
+		for (neighbor, weight) in std::mem::take(&mut adjacency[last]) {
+			adjacency[neighbor].remove(&last);
+			if neighbor != second_last {
+				*adjacency[second_last].entry(neighbor).or_default() += weight;
+				*adjacency[neighbor].entry(second_last).or_default() += weight;
+			}
+		}
+		let merged = std::mem::take(&mut members[last]);
+		members[second_last].extend(merged);
+		active.retain(|&node| node != last);
+	}
+
+	best.map(|(weight, nodes)| {
+		let mut side = vec![false; node_count];
+		for node in nodes {
+			side[node] = true;
+		}
+		MinCut { weight, side }
+	})
+}
+
+/// Adds the active nodes one by one, always picking the node that is most tightly connected to
+/// the nodes added so far. Returns the weight of the edges connecting the last node to all other
+/// nodes, together with the last two nodes that were added.
+fn minimum_cut_phase(
+	adjacency: &[HashMap<usize, u64>],
+	active: &[usize],
+	node_count: usize,
+) -> (u64, usize, usize) {
+	let mut connectivity = vec![0u64; node_count];
+	let mut added = vec![false; node_count];
+
+	// Disconnected nodes never get pushed otherwise, so start off with every node
+	let mut queue: BinaryHeap<(u64, usize)> = active.iter().map(|&node| (0, node)).collect();
+
+	let (mut second_last, mut last) = (active[0], active[0]);
+	let mut remaining = active.len();
+
+	while let Some((weight, node)) = queue.pop() {
+		if added[node] || weight != connectivity[node] {
+			// Already added, or an outdated entry
+			continue;
+		}
+
+		added[node] = true;
+		(second_last, last) = (last, node);
+
+		remaining -= 1;
+		if remaining == 0 {
+			break;
+		}
+
+		for (&neighbor, &weight) in &adjacency[node] {
+			if !added[neighbor] {
+				connectivity[neighbor] += weight;
+				queue.push((connectivity[neighbor], neighbor));
+			}
+		}
+	}
+
+	(connectivity[last], second_last, last)
+}
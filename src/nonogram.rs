@@ -0,0 +1,452 @@
+//! Solving lines of cells of which some are known to be operational or damaged, given the lengths
+//! of the groups of damaged cells. These are the rows of springs of day 12, or the rows and columns
+//! of a nonogram.
+
+use crate::{
+	grid2d::Grid2D,
+	memo::{memoize, Memo},
+};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Cell {
+	Operational,
+	Damaged,
+	#[default]
+	Unknown,
+}
+
+impl Cell {
+	pub fn to_char(self) -> char {
+		match self {
+			Self::Operational => '.',
+			Self::Damaged => '#',
+			Self::Unknown => '?',
+		}
+	}
+}
+
+impl TryFrom<u8> for Cell {
+	type Error = anyhow::Error;
+
+	fn try_from(value: u8) -> Result<Self, Self::Error> {
+		Ok(match value {
+			b'.' => Self::Operational,
+			b'#' => Self::Damaged,
+			b'?' => Self::Unknown,
+			_ => anyhow::bail!("Invalid cell '{}'", value as char),
+		})
+	}
+}
+
+/// Parses a line like `.??#`.
+///
+/// ```rust
+/// # use aoc2023::nonogram::{parse_line, Cell};
+/// assert_eq!(parse_line("#.?").unwrap(), [Cell::Damaged, Cell::Operational, Cell::Unknown]);
+/// assert_eq!(parse_line("#x").unwrap_err().to_string(), "Invalid cell 'x'");
+/// ```
+pub fn parse_line(line: &str) -> anyhow::Result<Vec<Cell>> {
+	line.bytes().map(Cell::try_from).collect()
+}
+
+/// Formats a line the same way it's parsed by [`parse_line`].
+pub fn format_line(line: &[Cell]) -> String {
+	line.iter().map(|cell| cell.to_char()).collect()
+}
+
+/// The amount of ways in which the unknown cells can be filled in, so the runs of damaged cells
+/// have the lengths in `groups`, in order.
+///
+/// ```rust
+/// # use aoc2023::nonogram::{count_arrangements, parse_line};
+/// assert_eq!(count_arrangements(&parse_line("???.###").unwrap(), &[1, 1, 3]), 1);
+/// assert_eq!(count_arrangements(&parse_line("?###????????").unwrap(), &[3, 2, 1]), 10);
+/// ```
+pub fn count_arrangements(line: &[Cell], groups: &[usize]) -> usize {
+	memoize((0, 0), &count_suffix_arrangements(line, groups))
+}
+
+/// Memoizes the amount of arrangements by the offsets in the line and the groups.
+type SuffixMemo<'m> = Memo<'m, (usize, usize), usize>;
+
+/// The function for a [`Memo`] that counts the arrangements of the suffixes of the line and the
+/// groups, starting at the offsets in the key.
+fn count_suffix_arrangements<'a>(
+	line: &'a [Cell],
+	groups: &'a [usize],
+) -> impl for<'m> Fn(&mut SuffixMemo<'m>, (usize, usize)) -> usize + 'a {
+	move |memo, (position, group)| {
+		let remaining_line = &line[position..];
+		let remaining_groups = &groups[group..];
+
+		if remaining_groups.is_empty() {
+			/* If we don't expect any more damaged groups, the rest of the cells shouldn't be
+			 * damaged. */
+			return if remaining_line.iter().all(|s| *s != Cell::Damaged) {
+				1
+			} else {
+				0
+			};
+		}
+
+		if remaining_line.is_empty() {
+			/* Line is empty, but more damaged cells are expected. */
+			return 0;
+		}
+
+		if remaining_line.len()
+			< remaining_groups.iter().sum::<usize>() + (remaining_groups.len() - 1)
+		{
+			/* The line's length should at least be the total sum of damaged cells, plus the
+			 * separators between the groups. */
+			return 0;
+		}
+
+		let match_operational = |memo: &mut Memo<_, _>| {
+			/* A group of operational cells can be considered like a single operational cell, so
+			 * the whole group can be skipped. */
+			let first_non_operational = remaining_line
+				.iter()
+				.skip(1)
+				.position(|s| *s != Cell::Operational)
+				.map(|x| position + x + 1)
+				.unwrap_or(line.len());
+			memo.get((first_non_operational, group))
+		};
+
+		let match_damaged = |memo: &mut Memo<_, _>| {
+			if !fits_group(remaining_line, remaining_groups[0]) {
+				return 0;
+			}
+
+			/* Skip the remaining damaged cells, and the separator after it (if it exists) */
+			let rest = (position + remaining_groups[0] + 1).min(line.len());
+			memo.get((rest, group + 1))
+		};
+
+		match remaining_line[0] {
+			Cell::Operational => match_operational(memo),
+			Cell::Damaged => match_damaged(memo),
+			Cell::Unknown => match_operational(memo) + match_damaged(memo),
+		}
+	}
+}
+
+/// Whether a group of `length` damaged cells can start at the beginning of the line.
+fn fits_group(line: &[Cell], length: usize) -> bool {
+	/* There should be enough cells left, none of them known to be operational, and the group
+	 * should be terminated by either an operational cell or the end of the line. */
+	line.len() >= length
+		&& !line[..length].contains(&Cell::Operational)
+		&& line.get(length) != Some(&Cell::Damaged)
+}
+
+/// Lists every arrangement of the line, lazily. Operational cells are tried before damaged ones, so
+/// the arrangements are sorted as if `.` comes before `#`.
+///
+/// ```rust
+/// # use aoc2023::nonogram::{arrangements, format_line, parse_line};
+/// let line = parse_line("?#??").unwrap();
+/// let all = arrangements(&line, &[2]).map(|a| format_line(&a)).collect::<Vec<_>>();
+/// assert_eq!(all, [".##.", "##.."]);
+/// ```
+pub fn arrangements<'a>(line: &'a [Cell], groups: &'a [usize]) -> Arrangements<'a> {
+	/* A single memo counts the arrangements of every suffix. Going from the end of the line to the
+	 * front, every count only needs the ones that are already known. */
+	let count = count_suffix_arrangements(line, groups);
+	let mut memo = Memo::new(&count);
+	let mut possible = vec![Vec::new(); line.len() + 1];
+	for position in (0..=line.len()).rev() {
+		possible[position] = (0..=groups.len())
+			.map(|group| memo.get((position, group)) > 0)
+			.collect();
+	}
+
+	let mut arrangements = Arrangements {
+		line,
+		groups,
+		stack: Vec::new(),
+		possible,
+	};
+	if arrangements.is_possible(0, 0) {
+		arrangements.stack.push((0, 0, Vec::new()));
+	}
+	arrangements
+}
+
+/// The iterator returned by [`arrangements`]. It only goes down paths that lead to at least one
+/// arrangement, so every step makes progress.
+#[derive(Debug, Clone)]
+pub struct Arrangements<'a> {
+	line: &'a [Cell],
+	groups: &'a [usize],
+	/// The position in the line, the next group, and the cells filled in so far.
+	stack: Vec<(usize, usize, Vec<Cell>)>,
+	/// Whether the suffixes of the line and the groups have any arrangement, indexed by their
+	/// offsets.
+	possible: Vec<Vec<bool>>,
+}
+
+impl Arrangements<'_> {
+	fn is_possible(&self, position: usize, group: usize) -> bool {
+		self.possible[position][group]
+	}
+}
+
+impl Iterator for Arrangements<'_> {
+	type Item = Vec<Cell>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		while let Some((position, group, cells)) = self.stack.pop() {
+			if position == self.line.len() {
+				return Some(cells);
+			}
+
+			/* Pushed first, so it's popped after the operational option */
+			if let Some(&length) = self.groups.get(group) {
+				let rest = (position + length + 1).min(self.line.len());
+				if fits_group(&self.line[position..], length) && self.is_possible(rest, group + 1) {
+					let mut cells = cells.clone();
+					cells.extend((position..rest).map(|i| {
+						if i < position + length {
+							Cell::Damaged
+						} else {
+							Cell::Operational
+						}
+					}));
+					self.stack.push((rest, group + 1, cells));
+				}
+			}
+
+			if self.line[position] != Cell::Damaged && self.is_possible(position + 1, group) {
+				let mut cells = cells;
+				cells.push(Cell::Operational);
+				self.stack.push((position + 1, group, cells));
+			}
+		}
+
+		None
+	}
+}
+
+/// Fills in the unknown cells that are the same in every arrangement of the line. Returns `None`
+/// if the line has no arrangements at all.
+///
+/// ```rust
+/// # use aoc2023::nonogram::{forced_cells, format_line, parse_line};
+/// let line = parse_line("???????").unwrap();
+/// assert_eq!(format_line(&forced_cells(&line, &[3, 2]).unwrap()), "?##??#?");
+///
+/// let line = parse_line("#.#").unwrap();
+/// assert_eq!(forced_cells(&line, &[3]), None);
+/// ```
+pub fn forced_cells(line: &[Cell], groups: &[usize]) -> Option<Vec<Cell>> {
+	let total = count_arrangements(line, groups);
+	if total == 0 {
+		return None;
+	}
+
+	/* Every arrangement has the cell either operational or damaged, so if all or none of them
+	 * still fit with the cell damaged, the cell is forced. */
+	let mut result = line.to_vec();
+	let mut attempt = line.to_vec();
+	for (i, cell) in line.iter().enumerate() {
+		if *cell != Cell::Unknown {
+			continue;
+		}
+
+		attempt[i] = Cell::Damaged;
+		let damaged = count_arrangements(&attempt, groups);
+		attempt[i] = Cell::Unknown;
+
+		if damaged == 0 {
+			result[i] = Cell::Operational;
+		} else if damaged == total {
+			result[i] = Cell::Damaged;
+		}
+	}
+
+	Some(result)
+}
+
+/// Repeats the line and the groups `times` times, with `separator` between the copies of the line.
+///
+/// ```rust
+/// # use aoc2023::nonogram::{format_line, parse_line, repeat, Cell};
+/// let line = parse_line(".#").unwrap();
+/// let (line, groups) = repeat(&line, &[1], 3, Cell::Unknown);
+/// assert_eq!(format_line(&line), ".#?.#?.#");
+/// assert_eq!(groups, [1, 1, 1]);
+/// ```
+pub fn repeat(
+	line: &[Cell],
+	groups: &[usize],
+	times: usize,
+	separator: Cell,
+) -> (Vec<Cell>, Vec<usize>) {
+	let mut new_line = Vec::with_capacity((line.len() + 1) * times);
+	let mut new_groups = Vec::with_capacity(groups.len() * times);
+
+	for i in 0..times {
+		if i != 0 {
+			new_line.push(separator);
+		}
+		new_line.extend(line);
+
+		new_groups.extend(groups);
+	}
+
+	(new_line, new_groups)
+}
+
+/// Solves a nonogram as far as possible by filling in the forced cells of every row and column,
+/// until nothing changes anymore. Returns whether every cell is known, or an error if a row or
+/// column can't be arranged.
+///
+/// ```rust
+/// # use aoc2023::{grid2d::Grid2D, nonogram::{solve_grid, Cell}};
+/// let mut grid = Grid2D::with_size(3, 3);
+/// let rows = [vec![3], vec![1], vec![1, 1]];
+/// let columns = [vec![1, 1], vec![2], vec![1, 1]];
+/// assert!(solve_grid(&mut grid, &rows, &columns).unwrap());
+/// assert_eq!(grid[(0usize, 2)], Cell::Damaged);
+/// assert_eq!(grid[(1usize, 2)], Cell::Operational);
+/// ```
+pub fn solve_grid(
+	grid: &mut Grid2D<Cell>,
+	row_groups: &[Vec<usize>],
+	column_groups: &[Vec<usize>],
+) -> anyhow::Result<bool> {
+	let (width, height) = (grid.width(), grid.height());
+	anyhow::ensure!(
+		row_groups.len() == height && column_groups.len() == width,
+		"Expected {height} rows and {width} columns of groups, but found {} and {}",
+		row_groups.len(),
+		column_groups.len()
+	);
+
+	let mut changed = true;
+	while changed {
+		changed = false;
+
+		for (y, groups) in row_groups.iter().enumerate() {
+			let line = (0..width).map(|x| grid[(x, y)]).collect::<Vec<_>>();
+			let solved = forced_cells(&line, groups)
+				.ok_or_else(|| anyhow::anyhow!("Row {y} can't be arranged"))?;
+			for (x, cell) in solved.into_iter().enumerate() {
+				changed |= grid[(x, y)] != cell;
+				grid[(x, y)] = cell;
+			}
+		}
+
+		for (x, groups) in column_groups.iter().enumerate() {
+			let line = (0..height).map(|y| grid[(x, y)]).collect::<Vec<_>>();
+			let solved = forced_cells(&line, groups)
+				.ok_or_else(|| anyhow::anyhow!("Column {x} can't be arranged"))?;
+			for (y, cell) in solved.into_iter().enumerate() {
+				changed |= grid[(x, y)] != cell;
+				grid[(x, y)] = cell;
+			}
+		}
+	}
+
+	Ok(grid.values().all(|cell| *cell != Cell::Unknown))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const EXAMPLE: [(&str, &[usize], usize); 6] = [
+		("???.###", &[1, 1, 3], 1),
+		(".??..??...?##.", &[1, 1, 3], 4),
+		("?#?#?#?#?#?#?#?", &[1, 3, 1, 6], 1),
+		("????.#...#...", &[4, 1, 1], 1),
+		("????.######..#####.", &[1, 6, 5], 4),
+		("?###????????", &[3, 2, 1], 10),
+	];
+
+	#[test]
+	fn test_count_arrangements() {
+		for (line, groups, expected) in EXAMPLE {
+			let line = parse_line(line).unwrap();
+			assert_eq!(count_arrangements(&line, groups), expected);
+		}
+
+		let unfolded = [1, 16384, 1, 16, 2500, 506250];
+		for ((line, groups, _), expected) in EXAMPLE.into_iter().zip(unfolded) {
+			let (line, groups) = repeat(&parse_line(line).unwrap(), groups, 5, Cell::Unknown);
+			assert_eq!(count_arrangements(&line, &groups), expected);
+		}
+	}
+
+	#[test]
+	fn test_arrangements() {
+		for (line, groups, expected) in EXAMPLE {
+			let line = parse_line(line).unwrap();
+			let all = arrangements(&line, groups).collect::<Vec<_>>();
+			assert_eq!(all.len(), expected);
+
+			for arrangement in &all {
+				assert_eq!(arrangement.len(), line.len());
+				assert!(!arrangement.contains(&Cell::Unknown));
+				assert!(line
+					.iter()
+					.zip(arrangement)
+					.all(|(a, b)| *a == Cell::Unknown || a == b));
+				assert_eq!(count_arrangements(arrangement, groups), 1);
+			}
+
+			/* In order, with operational cells before damaged ones */
+			let damaged = |a: &Vec<Cell>| a.iter().map(|c| *c == Cell::Damaged).collect::<Vec<_>>();
+			assert!(all.windows(2).all(|w| damaged(&w[0]) < damaged(&w[1])));
+		}
+
+		assert_eq!(arrangements(&parse_line("##").unwrap(), &[1]).count(), 0);
+		assert_eq!(
+			arrangements(&[], &[]).collect::<Vec<_>>(),
+			vec![Vec::<Cell>::new()]
+		);
+	}
+
+	#[test]
+	fn test_forced_cells() {
+		let forced = |line: &str, groups: &[usize]| {
+			forced_cells(&parse_line(line).unwrap(), groups).map(|line| format_line(&line))
+		};
+
+		assert_eq!(forced("???.###", &[1, 1, 3]).unwrap(), "#.#.###");
+		assert_eq!(forced("?###????????", &[3, 2, 1]).unwrap(), ".###.???????");
+		assert_eq!(forced("??????????", &[]).unwrap(), "..........");
+		assert_eq!(forced("?????", &[5]).unwrap(), "#####");
+		assert_eq!(forced("??.??", &[3]), None);
+	}
+
+	#[test]
+	fn test_solve_grid() {
+		/* A 5x5 picture of a plus sign with a filled corner */
+		let rows = [vec![1, 1], vec![1], vec![5], vec![1], vec![1]];
+		let columns = [vec![1], vec![1], vec![5], vec![1], vec![1, 1]];
+
+		let mut grid = Grid2D::<Cell>::with_size(5, 5);
+		assert!(solve_grid(&mut grid, &rows, &columns).unwrap());
+		let picture = (0..5usize)
+			.map(|y| {
+				(0..5)
+					.map(|x: usize| grid[(x, y)].to_char())
+					.collect::<String>()
+			})
+			.collect::<Vec<_>>();
+		assert_eq!(picture, ["..#.#", "..#..", "#####", "..#..", "..#.."]);
+
+		/* Two ways to place a diagonal, so nothing is forced */
+		let mut grid = Grid2D::with_size(2, 2);
+		let groups = [vec![1], vec![1]];
+		assert!(!solve_grid(&mut grid, &groups, &groups).unwrap());
+		assert!(grid.values().all(|cell| *cell == Cell::Unknown));
+
+		let mut grid = Grid2D::with_size(2, 2);
+		let error = solve_grid(&mut grid, &[vec![3], vec![]], &[vec![1], vec![1]]).unwrap_err();
+		assert_eq!(error.to_string(), "Row 0 can't be arranged");
+	}
+}
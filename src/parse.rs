@@ -0,0 +1,445 @@
+//! Small helpers for taking the puzzle inputs apart.
+
+use std::{fmt::Display, marker::PhantomData, str::FromStr};
+
+/// Splits the input on blank lines. Both `\n` and `\r\n` line endings are supported. Leading and
+/// trailing line endings of every block are removed, and empty blocks are skipped.
+///
+/// ```rust
+/// # use aoc2023::parse::blocks;
+/// let input = "a\nb\n\nc\n\n\nd\n";
+/// assert_eq!(blocks(input).collect::<Vec<_>>(), vec!["a\nb", "c", "d"]);
+///
+/// let input = "a\r\nb\r\n\r\nc\r\n";
+/// assert_eq!(blocks(input).collect::<Vec<_>>(), vec!["a\r\nb", "c"]);
+/// ```
+pub fn blocks(input: &str) -> impl Iterator<Item = &str> {
+	let mut rest = input;
+
+	std::iter::from_fn(move || loop {
+		if rest.is_empty() {
+			return None;
+		}
+
+		/* The block ends at the first blank line, which is skipped as well */
+		let (mut end, mut next) = (rest.len(), rest.len());
+		let mut offset = 0;
+		for line in rest.split_inclusive('\n') {
+			if line.trim_end_matches(['\r', '\n']).is_empty() {
+				(end, next) = (offset, offset + line.len());
+				break;
+			}
+			offset += line.len();
+		}
+
+		let block = rest[..end].trim_matches(['\r', '\n']);
+		rest = &rest[next..];
+		if !block.is_empty() {
+			return Some(block);
+		}
+	})
+}
+
+/// Splits a `key: value` line on its first colon, trimming the whitespace around both parts.
+///
+/// ```rust
+/// # use aoc2023::parse::key_value;
+/// assert_eq!(key_value("Game 12: 3 blue").unwrap(), ("Game 12", "3 blue"));
+/// assert!(key_value("3 blue").is_err());
+/// ```
+pub fn key_value(line: &str) -> anyhow::Result<(&str, &str)> {
+	let (key, value) = line
+		.split_once(':')
+		.ok_or_else(|| anyhow::anyhow!("Expected 'key: value', got '{line}'"))?;
+	Ok((key.trim(), value.trim()))
+}
+
+/// [`key_value`] for every non-empty line of the input.
+pub fn key_values(input: &str) -> impl Iterator<Item = anyhow::Result<(&str, &str)>> {
+	input
+		.lines()
+		.filter(|line| !line.trim().is_empty())
+		.map(key_value)
+}
+
+/// All integers in the line, parsed as `T`, ignoring anything in between them. A `-` right in
+/// front of the digits makes the integer negative, unless it directly follows another digit: then
+/// it separates two integers, like in `10-20`.
+///
+/// The integers are read from the line on the fly, without allocating. An integer that doesn't fit
+/// in `T` gives an error, after which the remaining integers can still be read.
+///
+/// ```rust
+/// # use aoc2023::parse::integers;
+/// let all = |line| integers::<i32>(line).collect::<anyhow::Result<Vec<_>>>().unwrap();
+/// assert_eq!(all("x=-3, y=4"), vec![-3, 4]);
+/// assert_eq!(all("Card  7: 41 48 | 83"), vec![7, 41, 48, 83]);
+/// assert_eq!(all("10-20,-5--6"), vec![10, 20, -5, -6]);
+///
+/// let mut unsigned = integers::<u32>("x=-3, y=4");
+/// assert_eq!(
+///     unsigned.next().unwrap().unwrap_err().to_string(),
+///     "Invalid integer '-3' in 'x=-3, y=4': invalid digit found in string"
+/// );
+/// assert_eq!(unsigned.next().unwrap().unwrap(), 4);
+/// ```
+pub fn integers<T>(line: &str) -> Integers<'_, T> {
+	Integers {
+		line,
+		position: 0,
+		target: PhantomData,
+	}
+}
+
+/// The iterator returned by [`integers`].
+#[derive(Debug, Clone)]
+pub struct Integers<'a, T> {
+	line: &'a str,
+	position: usize,
+	target: PhantomData<fn() -> T>,
+}
+
+impl<'a, T> Integers<'a, T> {
+	/// The text of the next integer, including its sign.
+	fn next_token(&mut self) -> Option<&'a str> {
+		let rest = &self.line[self.position..];
+		let digits_start = self.position + rest.find(|c: char| c.is_ascii_digit())?;
+		let end = self.line[digits_start..]
+			.find(|c: char| !c.is_ascii_digit())
+			.map_or(self.line.len(), |length| digits_start + length);
+		self.position = end;
+
+		let before = &self.line[..digits_start];
+		let is_negative = before.ends_with('-')
+			&& !before[..before.len() - 1].ends_with(|c: char| c.is_ascii_digit());
+		let start = digits_start - is_negative as usize;
+
+		Some(&self.line[start..end])
+	}
+}
+
+impl<'a, T> Iterator for Integers<'a, T>
+where
+	T: FromStr,
+	T::Err: Display,
+{
+	type Item = anyhow::Result<T>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let token = self.next_token()?;
+		Some(
+			token
+				.parse()
+				.map_err(|e| anyhow::anyhow!("Invalid integer '{token}' in '{}': {e}", self.line)),
+		)
+	}
+}
+
+/// Types that can be built from all integers in a line, see [`ints`].
+pub trait FromIntegers: Sized {
+	fn from_integers(line: &str) -> anyhow::Result<Self>;
+}
+
+impl<T, const N: usize> FromIntegers for [T; N]
+where
+	T: FromStr,
+	T::Err: Display,
+{
+	fn from_integers(line: &str) -> anyhow::Result<Self> {
+		let mut tokens = integers::<T>(line);
+		let mut values: [Option<T>; N] = std::array::from_fn(|_| None);
+		let expected = if N == 1 {
+			"1 integer".to_string()
+		} else {
+			format!("{N} integers")
+		};
+
+		for (i, value) in values.iter_mut().enumerate() {
+			let integer = tokens
+				.next()
+				.ok_or_else(|| anyhow::anyhow!("Expected {expected} in '{line}', but found {i}"))?;
+			*value = Some(integer?);
+		}
+
+		if tokens.next_token().is_some() {
+			let found = N + 1 + std::iter::from_fn(|| tokens.next_token()).count();
+			anyhow::bail!("Expected {expected} in '{line}', but found {found}");
+		}
+
+		Ok(values.map(Option::unwrap))
+	}
+}
+
+/// Parses exactly the integers in the line, as described in [`integers`], into a fixed-size
+/// array. Fails when the line contains a different amount of integers, or when one of them doesn't
+/// fit.
+///
+/// ```rust
+/// # use aoc2023::parse::ints;
+/// let [x1, y1, z1, x2, y2, z2] = ints::<[i64; 6]>("1,0,1~1,2,1").unwrap();
+/// assert_eq!((x1, y1, z1, x2, y2, z2), (1, 0, 1, 1, 2, 1));
+///
+/// assert!(ints::<[i64; 2]>("1 2 3").is_err());
+/// assert!(ints::<[u8; 1]>("-1").is_err());
+/// ```
+pub fn ints<A: FromIntegers>(line: &str) -> anyhow::Result<A> {
+	A::from_integers(line)
+}
+
+/// Reads typed tokens from a line, front to back. Errors mention the column where the line
+/// stopped matching, together with the full line.
+///
+/// ```rust
+/// # use aoc2023::parse::Scanner;
+/// let mut scanner = Scanner::new("AAA = (BBB, CCC)");
+/// let node = scanner.word().unwrap();
+/// scanner.expect(" = (").unwrap();
+/// let left = scanner.word().unwrap();
+/// scanner.expect(", ").unwrap();
+/// let right = scanner.word().unwrap();
+/// scanner.expect(")").unwrap();
+/// scanner.finish().unwrap();
+/// assert_eq!((node, left, right), ("AAA", "BBB", "CCC"));
+///
+/// let error = Scanner::new("x=12").expect("y=").unwrap_err();
+/// assert_eq!(error.to_string(), "Expected 'y=' at column 1 of 'x=12'");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Scanner<'a> {
+	input: &'a str,
+	position: usize,
+}
+
+impl<'a> Scanner<'a> {
+	pub fn new(input: &'a str) -> Self {
+		Self { input, position: 0 }
+	}
+
+	/// The byte offset of the next unread character.
+	pub fn position(&self) -> usize {
+		self.position
+	}
+
+	/// The part of the input that hasn't been read yet.
+	pub fn rest(&self) -> &'a str {
+		&self.input[self.position..]
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.rest().is_empty()
+	}
+
+	/// Reads the next character, if there is one.
+	pub fn char(&mut self) -> anyhow::Result<char> {
+		let c = self
+			.rest()
+			.chars()
+			.next()
+			.ok_or_else(|| self.error("Expected a character"))?;
+		self.position += c.len_utf8();
+		Ok(c)
+	}
+
+	/// Skips `literal` if the input continues with it, and returns whether it did.
+	pub fn eat(&mut self, literal: &str) -> bool {
+		let matches = self.rest().starts_with(literal);
+		if matches {
+			self.position += literal.len();
+		}
+		matches
+	}
+
+	/// Skips `literal`, or fails if the input doesn't continue with it.
+	pub fn expect(&mut self, literal: &str) -> anyhow::Result<()> {
+		if self.eat(literal) {
+			Ok(())
+		} else {
+			Err(self.error(format!("Expected '{literal}'")))
+		}
+	}
+
+	pub fn skip_whitespace(&mut self) {
+		let rest = self.rest();
+		self.position += rest.len() - rest.trim_start().len();
+	}
+
+	/// Reads a run of letters, digits and underscores.
+	pub fn word(&mut self) -> anyhow::Result<&'a str> {
+		let word = self.take_while(|c| c.is_alphanumeric() || c == '_');
+		if word.is_empty() {
+			return Err(self.error("Expected a word"));
+		}
+		Ok(word)
+	}
+
+	/// Reads an integer, with an optional sign, and parses it as `T`.
+	pub fn number<T>(&mut self) -> anyhow::Result<T>
+	where
+		T: FromStr,
+		T::Err: Display,
+	{
+		let start = self.position;
+		let sign = self.rest().starts_with(['-', '+']) as usize;
+		self.position += sign;
+
+		if self.take_while(|c| c.is_ascii_digit()).is_empty() {
+			self.position = start;
+			return Err(self.error("Expected a number"));
+		}
+
+		let number = &self.input[start..self.position];
+		number.parse().map_err(|e| {
+			self.position = start;
+			self.error(format!("Invalid number '{number}' ({e})"))
+		})
+	}
+
+	/// Reads everything up to `delimiter`, and skips the delimiter itself.
+	pub fn until(&mut self, delimiter: &str) -> anyhow::Result<&'a str> {
+		let length = self
+			.rest()
+			.find(delimiter)
+			.ok_or_else(|| self.error(format!("Expected '{delimiter}'")))?;
+		let text = &self.rest()[..length];
+		self.position += length + delimiter.len();
+		Ok(text)
+	}
+
+	/// Fails if there is any input left.
+	pub fn finish(&self) -> anyhow::Result<()> {
+		if self.is_empty() {
+			Ok(())
+		} else {
+			Err(self.error("Expected the end of the input"))
+		}
+	}
+
+	/// An error for the current position in the input.
+	pub fn error(&self, message: impl Display) -> anyhow::Error {
+		let column = self.input[..self.position].chars().count() + 1;
+		anyhow::anyhow!("{message} at column {column} of '{}'", self.input)
+	}
+
+	fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> &'a str {
+		let rest = self.rest();
+		let length = rest.find(|c| !predicate(c)).unwrap_or(rest.len());
+		self.position += length;
+		&rest[..length]
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_blocks() {
+		let blocks = |input| blocks(input).collect::<Vec<_>>();
+
+		assert_eq!(blocks(""), Vec::<&str>::new());
+		assert_eq!(blocks("\n\na\n"), vec!["a"]);
+		assert_eq!(
+			blocks("seeds: 1\r\n\r\nmap:\r\n1 2 3\r\n4 5 6\r\n\r\n\r\nend"),
+			vec!["seeds: 1", "map:\r\n1 2 3\r\n4 5 6", "end"]
+		);
+		assert_eq!(blocks("a\n\r\nb\r\n\nc"), vec!["a", "b", "c"]);
+	}
+
+	#[test]
+	fn test_integers() {
+		assert_eq!(integers::<i64>("").count(), 0);
+		assert_eq!(integers::<i64>("no numbers - here").count(), 0);
+		assert_eq!(
+			integers::<i64>("19, 13, 30 @ -2,  1, -2")
+				.collect::<anyhow::Result<Vec<_>>>()
+				.unwrap(),
+			vec![19, 13, 30, -2, 1, -2]
+		);
+		assert_eq!(
+			integers::<i64>("a--5 b-6 7-8")
+				.collect::<anyhow::Result<Vec<_>>>()
+				.unwrap(),
+			vec![-5, -6, 7, 8]
+		);
+		assert_eq!(
+			integers::<u128>("seeds: 340282366920938463463374607431768211455")
+				.next()
+				.unwrap()
+				.unwrap(),
+			u128::MAX
+		);
+	}
+
+	#[test]
+	fn test_integers_out_of_range() {
+		let results = integers::<u8>("1 -1 256 2").collect::<Vec<_>>();
+		assert_eq!(results.len(), 4);
+		assert_eq!(results[0].as_ref().unwrap(), &1);
+		assert_eq!(
+			results[1].as_ref().unwrap_err().to_string(),
+			"Invalid integer '-1' in '1 -1 256 2': invalid digit found in string"
+		);
+		assert!(results[2].is_err());
+		assert_eq!(results[3].as_ref().unwrap(), &2);
+
+		assert!(integers::<u8>("1 256")
+			.collect::<anyhow::Result<Vec<_>>>()
+			.is_err());
+	}
+
+	#[test]
+	fn test_ints() {
+		assert_eq!(ints::<[i16; 0]>("").unwrap(), []);
+		assert_eq!(ints::<[i16; 2]>("x=-3, y=4").unwrap(), [-3, 4]);
+		assert_eq!(
+			ints::<[i64; 3]>("1 2").unwrap_err().to_string(),
+			"Expected 3 integers in '1 2', but found 2"
+		);
+		assert_eq!(
+			ints::<[i64; 1]>("1 2 3").unwrap_err().to_string(),
+			"Expected 1 integer in '1 2 3', but found 3"
+		);
+		assert_eq!(
+			ints::<[u8; 1]>("none").unwrap_err().to_string(),
+			"Expected 1 integer in 'none', but found 0"
+		);
+		assert!(ints::<[u8; 1]>("256").is_err());
+	}
+
+	#[test]
+	fn test_scanner() {
+		let mut scanner = Scanner::new("a<2006:qkq");
+		assert_eq!(scanner.word().unwrap(), "a");
+		assert_eq!(scanner.char().unwrap(), '<');
+		assert_eq!(scanner.number::<u16>().unwrap(), 2006);
+		assert!(!scanner.eat(">"));
+		scanner.expect(":").unwrap();
+		assert_eq!(scanner.word().unwrap(), "qkq");
+		scanner.finish().unwrap();
+		assert!(scanner.char().is_err());
+	}
+
+	#[test]
+	fn test_scanner_errors() {
+		let mut scanner = Scanner::new("rn=300");
+		assert_eq!(scanner.until("=").unwrap(), "rn");
+		assert_eq!(
+			scanner.number::<u8>().unwrap_err().to_string(),
+			"Invalid number '300' (number too large to fit in target type) at column 4 of 'rn=300'"
+		);
+		assert_eq!(scanner.position(), 3);
+		assert_eq!(
+			scanner.word().unwrap().parse::<u16>().unwrap(),
+			300,
+			"A failed number shouldn't consume any input"
+		);
+
+		let mut scanner = Scanner::new("x=-");
+		scanner.expect("x=").unwrap();
+		assert_eq!(
+			scanner.number::<i64>().unwrap_err().to_string(),
+			"Expected a number at column 3 of 'x=-'"
+		);
+		assert_eq!(scanner.rest(), "-");
+	}
+}
@@ -0,0 +1,281 @@
+use std::{
+	collections::{HashMap, VecDeque},
+	str::FromStr,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Pulse {
+	Low,
+	High,
+}
+
+/// A pulse travelling between two modules, identified by their index in the [`Network`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Signal {
+	pub from: usize,
+	pub to: usize,
+	pub pulse: Pulse,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PulseCount {
+	pub low: usize,
+	pub high: usize,
+}
+
+impl std::ops::Add for PulseCount {
+	type Output = Self;
+
+	fn add(self, rhs: Self) -> Self::Output {
+		Self {
+			low: self.low + rhs.low,
+			high: self.high + rhs.high,
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ModuleKind {
+	Broadcaster,
+	FlipFlop {
+		on: bool,
+	},
+	/// Remembers the most recent pulse of each input, in the same order as `Module::inputs`.
+	Conjunction {
+		memory: Vec<Pulse>,
+	},
+	/// Modules that are only mentioned as a destination, and don't send any pulses.
+	Output,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Module {
+	kind: ModuleKind,
+	inputs: Vec<usize>,
+	outputs: Vec<usize>,
+}
+
+impl Module {
+	/// Handles an incoming signal, and returns the pulse to send to all outputs (if any).
+	fn receive(&mut self, from: usize, pulse: Pulse) -> Option<Pulse> {
+		match &mut self.kind {
+			ModuleKind::Broadcaster => Some(pulse),
+			ModuleKind::FlipFlop { .. } if pulse == Pulse::High => None,
+			ModuleKind::FlipFlop { on } => {
+				*on = !*on;
+				Some(if *on { Pulse::High } else { Pulse::Low })
+			}
+			ModuleKind::Conjunction { memory } => {
+				let input = self.inputs.iter().position(|&i| i == from).unwrap();
+				memory[input] = pulse;
+
+				Some(if memory.iter().all(|&p| p == Pulse::High) {
+					Pulse::Low
+				} else {
+					Pulse::High
+				})
+			}
+			ModuleKind::Output => None,
+		}
+	}
+}
+
+/// A network of modules passing pulses to each other. Every button press sends a low pulse to
+/// the broadcaster, and the pulses are then processed in the order they were sent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Network {
+	names: Vec<String>,
+	modules: Vec<Module>,
+	broadcaster: usize,
+}
+
+/// The button isn't a module in the network, so it gets an index that no module can have.
+pub const BUTTON: usize = usize::MAX;
+
+impl Network {
+	pub fn index_of(&self, name: &str) -> Option<usize> {
+		self.names.iter().position(|n| n == name)
+	}
+
+	pub fn name(&self, index: usize) -> &str {
+		&self.names[index]
+	}
+
+	/// The modules that send pulses to the given module.
+	pub fn inputs(&self, index: usize) -> &[usize] {
+		&self.modules[index].inputs
+	}
+
+	/// Presses the button once, and processes all pulses until the network settles. Every signal
+	/// is passed to `observer` as it is delivered.
+	///
+	/// ```rust
+	/// # use aoc2023::pulse::Network;
+	/// let input = "broadcaster -> a\n%a -> inv, con\n&inv -> b\n%b -> con\n&con -> output";
+	/// let mut network: Network = input.parse().unwrap();
+	///
+	/// let count = network.press_button(|_| {});
+	/// assert_eq!((count.low, count.high), (4, 4));
+	/// ```
+	pub fn press_button(&mut self, mut observer: impl FnMut(&Signal)) -> PulseCount {
+		let mut count = PulseCount::default();
+		let mut queue = VecDeque::from([Signal {
+			from: BUTTON,
+			to: self.broadcaster,
+			pulse: Pulse::Low,
+		}]);
+
+		while let Some(signal) = queue.pop_front() {
+			observer(&signal);
+
+			match signal.pulse {
+				Pulse::Low => count.low += 1,
+				Pulse::High => count.high += 1,
+			}
+
+			let module = &mut self.modules[signal.to];
+			if let Some(pulse) = module.receive(signal.from, signal.pulse) {
+				queue.extend(module.outputs.iter().map(|&to| Signal {
+					from: signal.to,
+					to,
+					pulse,
+				}));
+			}
+		}
+
+		count
+	}
+
+	/// Keeps pressing the button until each of the given modules has sent a high pulse, and
+	/// returns the number of the press (starting at 1) on which that first happened for each of
+	/// them. Doesn't return if one of the modules never sends a high pulse.
+	pub fn first_high_pulses(&mut self, modules: &[usize]) -> Vec<usize> {
+		let mut presses: Vec<Option<usize>> = vec![None; modules.len()];
+
+		for press in 1.. {
+			self.press_button(|signal| {
+				if signal.pulse != Pulse::High {
+					return;
+				}
+
+				if let Some(i) = modules.iter().position(|&m| m == signal.from) {
+					presses[i].get_or_insert(press);
+				}
+			});
+
+			if presses.iter().all(Option::is_some) {
+				break;
+			}
+		}
+
+		presses.into_iter().flatten().collect()
+	}
+}
+
+impl FromStr for Network {
+	type Err = anyhow::Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let definitions = s
+			.lines()
+			.map(|line| {
+				let (module, outputs) = line
+					.split_once(" -> ")
+					.ok_or_else(|| anyhow::anyhow!("Expected ' -> ' in '{line}'"))?;
+
+				let (kind, name) = match module.as_bytes().first() {
+					Some(b'%') => (ModuleKind::FlipFlop { on: false }, &module[1..]),
+					Some(b'&') => (ModuleKind::Conjunction { memory: Vec::new() }, &module[1..]),
+					_ if module == "broadcaster" => (ModuleKind::Broadcaster, module),
+					_ => anyhow::bail!("Unknown module '{module}'"),
+				};
+
+				Ok((name, kind, outputs.split(", ").collect::<Vec<_>>()))
+			})
+			.collect::<anyhow::Result<Vec<_>>>()?;
+
+		let mut indices: HashMap<&str, usize> = HashMap::new();
+		let mut names = Vec::new();
+		let mut modules = Vec::new();
+
+		// Modules that only appear as outputs are added after the ones that are defined
+		let all_names = definitions.iter().map(|(name, ..)| *name).chain(
+			definitions
+				.iter()
+				.flat_map(|(_, _, outputs)| outputs.iter().copied()),
+		);
+		for name in all_names {
+			indices.entry(name).or_insert_with(|| {
+				names.push(name.to_string());
+				modules.push(Module {
+					kind: ModuleKind::Output,
+					inputs: Vec::new(),
+					outputs: Vec::new(),
+				});
+				names.len() - 1
+			});
+		}
+
+		for (name, kind, outputs) in definitions {
+			let index = indices[name];
+			modules[index].kind = kind;
+
+			for output in outputs {
+				let output = indices[output];
+				modules[index].outputs.push(output);
+				modules[output].inputs.push(index);
+			}
+		}
+
+		for module in &mut modules {
+			if let ModuleKind::Conjunction { memory } = &mut module.kind {
+				*memory = vec![Pulse::Low; module.inputs.len()];
+			}
+		}
+
+		let broadcaster = *indices
+			.get("broadcaster")
+			.ok_or_else(|| anyhow::anyhow!("Expected a broadcaster"))?;
+
+		Ok(Self {
+			names,
+			modules,
+			broadcaster,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const LOOPING: &str = "broadcaster -> a, b, c\n%a -> b\n%b -> c\n%c -> inv\n&inv -> a";
+	const WITH_OUTPUT: &str =
+		"broadcaster -> a\n%a -> inv, con\n&inv -> b\n%b -> con\n&con -> output";
+
+	fn press_button_times(network: &mut Network, times: usize) -> PulseCount {
+		(0..times)
+			.map(|_| network.press_button(|_| {}))
+			.fold(PulseCount::default(), |acc, it| acc + it)
+	}
+
+	#[test]
+	fn test_pulse_counts() {
+		let mut network: Network = LOOPING.parse().unwrap();
+		let count = press_button_times(&mut network, 1000);
+		assert_eq!((count.low, count.high), (8000, 4000));
+
+		let mut network: Network = WITH_OUTPUT.parse().unwrap();
+		let count = press_button_times(&mut network, 1000);
+		assert_eq!((count.low, count.high), (4250, 2750));
+	}
+
+	#[test]
+	fn test_first_high_pulses() {
+		let mut network: Network = WITH_OUTPUT.parse().unwrap();
+
+		let con = network.index_of("con").unwrap();
+		let inv = network.index_of("inv").unwrap();
+		assert_eq!(network.first_high_pulses(&[con, inv]), vec![1, 2]);
+	}
+}
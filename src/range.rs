@@ -1,4 +1,66 @@
-use std::ops::Range;
+use std::ops::{Bound, Range, RangeBounds, RangeInclusive};
+
+use crate::math::Integer;
+
+/// Ranges over integers, which can be converted to and from their first and last value. This
+/// allows the functions in this module to produce ranges of the same kind as their input. Working
+/// with the last value instead of the end means ranges like `0..=u8::MAX` don't overflow.
+pub trait Interval<Idx: Integer>: RangeBounds<Idx> + Clone + Sized {
+	/// The range from `first` up to and including `last`. `last` shouldn't come before `first`.
+	fn from_bounds(first: Idx, last: Idx) -> Self;
+
+	/// An empty range, starting at `point`.
+	fn empty(point: Idx) -> Self;
+
+	/// An empty range right after `last`, which is a value in a range of this kind.
+	fn empty_after(last: Idx) -> Self;
+
+	/// The first and the last value in the range, or `None` if it's empty.
+	fn bounds(&self) -> Option<(Idx, Idx)>;
+}
+
+impl<Idx: Integer> Interval<Idx> for Range<Idx> {
+	fn from_bounds(first: Idx, last: Idx) -> Self {
+		// A `Range` can't contain the maximum value, so neither can any range made from its bounds
+		first..(last + Idx::ONE)
+	}
+
+	fn empty(point: Idx) -> Self {
+		point..point
+	}
+
+	fn empty_after(last: Idx) -> Self {
+		// `last` is in a `Range`, so it isn't the maximum value
+		Self::empty(last + Idx::ONE)
+	}
+
+	fn bounds(&self) -> Option<(Idx, Idx)> {
+		(self.start < self.end).then(|| (self.start, self.end - Idx::ONE))
+	}
+}
+
+impl<Idx: Integer> Interval<Idx> for RangeInclusive<Idx> {
+	fn from_bounds(first: Idx, last: Idx) -> Self {
+		first..=last
+	}
+
+	fn empty(point: Idx) -> Self {
+		// Adding to `point` could overflow, so create the empty range the other way around
+		if point == Idx::MAX {
+			point..=(point - Idx::ONE)
+		} else {
+			(point + Idx::ONE)..=point
+		}
+	}
+
+	fn empty_after(last: Idx) -> Self {
+		Self::empty(last)
+	}
+
+	fn bounds(&self) -> Option<(Idx, Idx)> {
+		(self.start() <= self.end()).then(|| (*self.start(), *self.end()))
+	}
+}
 
 /// ```rust
 /// # use aoc2023::range::overlaps;
@@ -26,19 +88,80 @@ use std::ops::Range;
 /// // Containing
 /// assert!(overlaps(&(0..30), &(10..20)));
 /// assert!(overlaps(&(10..20), &(0..30)));
+///
+/// // Inclusive ranges
+/// assert!(overlaps(&(0..=10), &(10..=20)));
+/// assert!(!overlaps(&(0..=9), &(10..=20)));
+/// assert!(overlaps(&(0..=10), &(10..20)));
+///
+/// // Unbounded ranges
+/// assert!(overlaps(&(..10), &(..0)));
+/// assert!(!overlaps(&(10..), &(..10)));
 /// ```
-pub fn overlaps<Idx>(r1: &Range<Idx>, r2: &Range<Idx>) -> bool
+pub fn overlaps<Idx, R1, R2>(r1: &R1, r2: &R2) -> bool
 where
 	Idx: PartialOrd<Idx>,
+	R1: RangeBounds<Idx>,
+	R2: RangeBounds<Idx>,
 {
-	!(r1.end <= r2.start || r2.end <= r1.start)
+	!(ends_before(r1, r2) || ends_before(r2, r1))
 }
 
-pub fn contains<Idx>(outer: &Range<Idx>, inner: &Range<Idx>) -> bool
+/// ```rust
+/// # use aoc2023::range::contains;
+/// assert!(contains(&(0..10), &(0..10)));
+/// assert!(contains(&(0..10), &(2..8)));
+/// assert!(!contains(&(0..10), &(5..15)));
+/// assert!(!contains(&(2..8), &(0..10)));
+///
+/// // Inclusive ranges
+/// assert!(contains(&(0..=10), &(5..=10)));
+/// assert!(!contains(&(0..=10), &(5..=11)));
+///
+/// // Unbounded ranges
+/// assert!(contains(&(..), &(0..10)));
+/// assert!(contains(&(0..), &(10..)));
+/// assert!(!contains(&(10..), &(0..)));
+/// ```
+pub fn contains<Idx, R1, R2>(outer: &R1, inner: &R2) -> bool
 where
 	Idx: PartialOrd<Idx>,
+	R1: RangeBounds<Idx>,
+	R2: RangeBounds<Idx>,
 {
-	outer.start <= inner.start && inner.end <= outer.end
+	use Bound::*;
+
+	let starts_before = match (outer.start_bound(), inner.start_bound()) {
+		(Unbounded, _) => true,
+		(_, Unbounded) => false,
+		(Excluded(outer), Included(inner)) => outer < inner,
+		(Included(outer) | Excluded(outer), Included(inner) | Excluded(inner)) => outer <= inner,
+	};
+
+	let ends_after = match (outer.end_bound(), inner.end_bound()) {
+		(Unbounded, _) => true,
+		(_, Unbounded) => false,
+		(Excluded(outer), Included(inner)) => inner < outer,
+		(Included(outer) | Excluded(outer), Included(inner) | Excluded(inner)) => inner <= outer,
+	};
+
+	starts_before && ends_after
+}
+
+/// Whether all values in `range` come before the values in `other`.
+fn ends_before<Idx, R1, R2>(range: &R1, other: &R2) -> bool
+where
+	Idx: PartialOrd<Idx>,
+	R1: RangeBounds<Idx>,
+	R2: RangeBounds<Idx>,
+{
+	use Bound::*;
+
+	match (range.end_bound(), other.start_bound()) {
+		(Unbounded, _) | (_, Unbounded) => false,
+		(Included(end), Included(start)) => end < start,
+		(Included(end) | Excluded(end), Included(start) | Excluded(start)) => end <= start,
+	}
 }
 
 /// ```rust
@@ -68,16 +191,227 @@ where
 /// // Containing, not touching
 /// assert_eq!(Some(20..30), intersection(&(10..40), &(20..30)));
 /// assert_eq!(Some(20..30), intersection(&(20..30), &(10..40)));
+///
+/// // Inclusive ranges
+/// assert_eq!(Some(20..=20), intersection(&(10..=20), &(20..=30)));
+/// assert_eq!(None, intersection(&(10..=19), &(20..=30)));
+/// ```
+pub fn intersection<Idx, R>(r1: &R, r2: &R) -> Option<R>
+where
+	Idx: Integer,
+	R: Interval<Idx>,
+{
+	let ((first1, last1), (first2, last2)) = (r1.bounds()?, r2.bounds()?);
+
+	let first = first1.max(first2);
+	let last = last1.min(last2);
+	(first <= last).then(|| R::from_bounds(first, last))
+}
+
+/// Combines two ranges into one, if they overlap or touch.
+///
+/// ```rust
+/// # use aoc2023::range::union;
+/// // Separate
+/// assert_eq!(None, union(&(0..10), &(20..30)));
+///
+/// // Touching
+/// assert_eq!(Some(0..20), union(&(0..10), &(10..20)));
+/// assert_eq!(Some(0..20), union(&(10..20), &(0..10)));
+///
+/// // Partial overlap
+/// assert_eq!(Some(0..30), union(&(0..20), &(10..30)));
+///
+/// // Containing
+/// assert_eq!(Some(0..30), union(&(0..30), &(10..20)));
+///
+/// // Inclusive ranges touch when there are no values between them
+/// assert_eq!(Some(0..=20), union(&(0..=9), &(10..=20)));
+/// assert_eq!(None, union(&(0..=8), &(10..=20)));
+/// ```
+pub fn union<Idx, R>(r1: &R, r2: &R) -> Option<R>
+where
+	Idx: Integer,
+	R: Interval<Idx>,
+{
+	let ((first1, last1), (first2, last2)) = (r1.bounds()?, r2.bounds()?);
+
+	/* Whether the range ending at `last` reaches the one starting at `first`. The addition can't
+	 * overflow, as `last` comes before `first`. */
+	let reaches = |last: Idx, first: Idx| last >= first || last + Idx::ONE == first;
+
+	(reaches(last1, first2) && reaches(last2, first1))
+		.then(|| R::from_bounds(first1.min(first2), last1.max(last2)))
+}
+
+/// The parts of `r1` that aren't in `r2`. Yields up to two ranges, in ascending order.
+///
+/// ```rust
+/// # use aoc2023::range::Interval;
+/// fn difference<R: Interval<i32>>(r1: R, r2: R) -> Vec<R> {
+///     aoc2023::range::difference(&r1, &r2).collect()
+/// }
+///
+/// // No overlap
+/// assert_eq!(vec![10..20], difference(10..20, 30..40));
+///
+/// // Partial overlap
+/// assert_eq!(vec![10..20], difference(10..30, 20..40));
+/// assert_eq!(vec![30..40], difference(20..40, 10..30));
+///
+/// // Containing
+/// assert_eq!(vec![10..20, 30..40], difference(10..40, 20..30));
+/// assert_eq!(Vec::<std::ops::Range<i32>>::new(), difference(20..30, 10..40));
+///
+/// // Inclusive ranges
+/// let pieces = difference(10..=40, 20..=30);
+/// assert_eq!(vec![10..=19, 31..=40], pieces);
+/// ```
+pub fn difference<Idx, R>(r1: &R, r2: &R) -> impl Iterator<Item = R>
+where
+	Idx: Integer,
+	R: Interval<Idx>,
+{
+	let pieces = match (r1.bounds(), r2.bounds()) {
+		(None, _) => [None, None],
+		(Some(bounds), None) => [Some(bounds), None],
+		(Some((first1, last1)), Some((first2, last2))) => {
+			/* The subtraction and addition can't overflow, because there's a value of `r1` on the
+			 * other side. */
+			let before = (first1 < first2).then(|| (first1, last1.min(first2 - Idx::ONE)));
+			let after = (last2 < last1).then(|| (first1.max(last2 + Idx::ONE), last1));
+			[before, after]
+		}
+	};
+
+	pieces
+		.into_iter()
+		.flatten()
+		.map(|(first, last)| R::from_bounds(first, last))
+}
+
+/// Splits the range in the part before `point`, and the part starting at `point`. Either part is
+/// empty if `point` lies outside of the range, in which case it's placed at the range's edge.
+///
+/// ```rust
+/// # use aoc2023::range::split_at;
+/// assert_eq!((0..5, 5..10), split_at(&(0..10), 5));
+/// assert_eq!((0..0, 0..10), split_at(&(0..10), 0));
+/// assert_eq!((0..10, 10..10), split_at(&(0..10), 20));
+/// assert_eq!((5..5, 5..10), split_at(&(5..10), -20));
+///
+/// assert_eq!((0..=4, 5..=10), split_at(&(0..=10), 5));
+/// assert!(split_at(&(0..=10), 0).0.is_empty());
+///
+/// // Ranges up to the maximum value
+/// assert_eq!((0..=99, 100..=255), split_at(&(0u8..=255), 100));
+/// assert!(split_at(&(0u8..=255), 0).0.is_empty());
 /// ```
-pub fn intersection<Idx>(r1: &Range<Idx>, r2: &Range<Idx>) -> Option<Range<Idx>>
+pub fn split_at<Idx, R>(range: &R, point: Idx) -> (R, R)
 where
-	Idx: PartialOrd<Idx> + Ord + Copy,
+	Idx: Integer,
+	R: Interval<Idx>,
 {
-	if !overlaps(r1, r2) {
-		return None;
+	let Some((first, last)) = range.bounds() else {
+		return (range.clone(), range.clone());
+	};
+
+	let before = if point > first {
+		R::from_bounds(first, last.min(point - Idx::ONE))
+	} else {
+		R::empty(first)
+	};
+	let after = if point <= last {
+		R::from_bounds(first.max(point), last)
+	} else {
+		R::empty_after(last)
+	};
+	(before, after)
+}
+
+/// The amount of values in the range, as the unsigned type of the same size. Unlike
+/// `ExactSizeIterator::len`, this also works for ranges of 64-bit and signed integers. Inclusive
+/// ranges covering every value of the type, like `0u8..=255` or `-128i8..=127`, have one value too
+/// many to fit and overflow.
+///
+/// ```rust
+/// # use aoc2023::range::len;
+/// assert_eq!(10, len(&(0u32..10)));
+/// assert_eq!(11, len(&(0u32..=10)));
+/// assert_eq!(20u64, len(&(-10i64..10)));
+///
+/// // Empty ranges
+/// assert_eq!(0, len(&(10u32..10)));
+/// assert_eq!(0, len(&(10u32..0)));
+/// assert_eq!(0, len(&(1u8..=0)));
+///
+/// // Ranges up to the maximum value
+/// assert_eq!(255, len(&(1u8..=255)));
+/// assert_eq!(2, len(&((u64::MAX - 1)..=u64::MAX)));
+///
+/// // Signed ranges that are wider than half of the type
+/// assert_eq!(200u8, len(&(-100i8..100)));
+/// assert_eq!(255u8, len(&(i8::MIN..i8::MAX)));
+/// assert_eq!(255u8, len(&(-127i8..=127)));
+/// assert_eq!(u64::MAX, len(&(i64::MIN..i64::MAX)));
+/// ```
+pub fn len<Idx, R>(range: &R) -> Idx::Unsigned
+where
+	Idx: Integer,
+	R: Interval<Idx>,
+{
+	match range.bounds() {
+		Some((first, last)) => first.abs_diff(last) + Idx::Unsigned::ONE,
+		None => Idx::Unsigned::ZERO,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_maximum_values() {
+		let full = 0u8..=255;
+
+		assert_eq!(intersection(&full, &(200..=255)), Some(200..=255));
+		assert_eq!(union(&(0..=254), &(255..=255)), Some(full.clone()));
+		assert_eq!(
+			union(&(i64::MIN..=-1), &(0..=i64::MAX)),
+			Some(i64::MIN..=i64::MAX)
+		);
+		assert_eq!(
+			difference(&full, &(100..=200)).collect::<Vec<_>>(),
+			vec![0..=99, 201..=255]
+		);
+		assert_eq!(
+			difference(&full, &(0..=254)).collect::<Vec<_>>(),
+			vec![255..=255]
+		);
+		assert_eq!(difference(&full, &full).count(), 0);
+
+		let (before, after) = split_at(&full, 255);
+		assert_eq!((before, after), (0..=254, 255..=255));
+		let empty = RangeInclusive::<u8>::empty(255);
+		assert!(empty.is_empty());
+		assert_eq!(len(&empty), 0);
+		let (before, after) = split_at(&(0u8..=255), 0);
+		assert_eq!((before.is_empty(), after), (true, full.clone()));
+		let (before, after) = split_at(&(0u8..=254), 255);
+		assert_eq!((before, *after.start()), (0..=254, 255));
+		assert!(after.is_empty());
+		assert_eq!(len(&(i64::MIN..=i64::MAX - 1)), u64::MAX);
 	}
 
-	let start = r1.start.max(r2.start);
-	let end = r1.end.min(r2.end);
-	Some(start..end)
+	#[test]
+	fn test_empty_ranges() {
+		assert_eq!(intersection(&(5..5), &(0..10)), None);
+		assert_eq!(union(&(5..5), &(0..10)), None);
+		assert_eq!(
+			difference(&(0..10), &(5..5)).collect::<Vec<_>>(),
+			vec![0..10]
+		);
+		assert_eq!(difference(&(5..5), &(0..10)).count(), 0);
+		assert_eq!(split_at(&(5..5), 3), (5..5, 5..5));
+	}
 }
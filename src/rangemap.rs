@@ -0,0 +1,226 @@
+use std::ops::{Add, Range, Sub};
+
+use crate::{range::overlaps, rangeset::RangeSet};
+
+/// A piecewise mapping of values, defined by rules that shift a source range so it starts at a
+/// destination. Values that aren't covered by any rule map to themselves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeMap<Idx> {
+	/// Sorted by the start of the source range, and never overlapping.
+	rules: Vec<MappingRule<Idx>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct MappingRule<Idx> {
+	source: Range<Idx>,
+	destination: Idx,
+}
+
+impl<Idx> RangeMap<Idx>
+where
+	Idx: Ord + Copy + Add<Output = Idx> + Sub<Output = Idx>,
+{
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Maps `source` so it starts at `destination`.
+	///
+	/// Panics if `source` overlaps with the source range of a rule that was inserted earlier.
+	///
+	/// ```rust
+	/// # use aoc2023::rangemap::RangeMap;
+	/// let mut map = RangeMap::new();
+	/// map.insert(98..100, 50);
+	/// map.insert(50..98, 52);
+	///
+	/// assert_eq!(map.get(0), 0);
+	/// assert_eq!(map.get(50), 52);
+	/// assert_eq!(map.get(97), 99);
+	/// assert_eq!(map.get(98), 50);
+	/// assert_eq!(map.get(100), 100);
+	/// ```
+	pub fn insert(&mut self, source: Range<Idx>, destination: Idx) {
+		if source.is_empty() {
+			return;
+		}
+
+		let index = self.rules.partition_point(|r| r.source.end <= source.start);
+		if let Some(rule) = self.rules.get(index) {
+			assert!(
+				!overlaps(&rule.source, &source),
+				"Mapping rules shouldn't overlap"
+			);
+		}
+
+		self.rules.insert(
+			index,
+			MappingRule {
+				source,
+				destination,
+			},
+		);
+	}
+
+	pub fn get(&self, item: Idx) -> Idx {
+		let index = self.rules.partition_point(|r| r.source.end <= item);
+		match self.rules.get(index) {
+			Some(rule) if rule.source.contains(&item) => {
+				rule.destination + (item - rule.source.start)
+			}
+			_ => item,
+		}
+	}
+
+	/// Maps a whole range at once. The range is split at the boundaries of the rules, and every
+	/// piece is mapped separately. The pieces are returned in the order of the input range.
+	///
+	/// ```rust
+	/// # use aoc2023::rangemap::RangeMap;
+	/// let mut map = RangeMap::new();
+	/// map.insert(20..40, 1020);
+	/// map.insert(60..80, 2060);
+	///
+	/// // Not covered by any rule
+	/// assert_eq!(map.map_range(45..55), vec![45..55]);
+	///
+	/// // Partially covered
+	/// assert_eq!(map.map_range(0..40), vec![0..20, 1020..1040]);
+	/// assert_eq!(map.map_range(20..60), vec![1020..1040, 40..60]);
+	///
+	/// // Exactly covered
+	/// assert_eq!(map.map_range(20..40), vec![1020..1040]);
+	///
+	/// // Spanning multiple rules
+	/// assert_eq!(
+	///     map.map_range(0..100),
+	///     vec![0..20, 1020..1040, 40..60, 2060..2080, 80..100]
+	/// );
+	/// ```
+	pub fn map_range(&self, range: Range<Idx>) -> Vec<Range<Idx>> {
+		self.split(range)
+			.into_iter()
+			.map(|(piece, destination)| destination..(destination + (piece.end - piece.start)))
+			.collect()
+	}
+
+	/// Creates a single map that has the same effect as applying `self` first, and `next`
+	/// afterwards.
+	///
+	/// ```rust
+	/// # use aoc2023::rangemap::RangeMap;
+	/// let mut first = RangeMap::new();
+	/// first.insert(0..10, 100);
+	///
+	/// let mut second = RangeMap::new();
+	/// second.insert(105..110, 200);
+	/// second.insert(20..30, 300);
+	///
+	/// let composed = first.compose(&second);
+	///
+	/// for item in 0..50 {
+	///     assert_eq!(composed.get(item), second.get(first.get(item)));
+	/// }
+	/// ```
+	pub fn compose(&self, next: &Self) -> Self {
+		let mut result = Self::new();
+
+		// Values covered by `self` get mapped by `self` first, and then by `next`
+		for rule in &self.rules {
+			let length = rule.source.end - rule.source.start;
+			let mapped = rule.destination..(rule.destination + length);
+
+			for (piece, destination) in next.split(mapped) {
+				let start = rule.source.start + (piece.start - rule.destination);
+				let end = start + (piece.end - piece.start);
+				result.insert_non_identity(start..end, destination);
+			}
+		}
+
+		// Values not covered by `self` are only affected by `next`
+		let domain = self
+			.rules
+			.iter()
+			.map(|r| r.source.clone())
+			.collect::<RangeSet<_>>();
+		for rule in &next.rules {
+			let uncovered = RangeSet::from_iter([rule.source.clone()]).difference(&domain);
+			for piece in uncovered.iter() {
+				let destination = rule.destination + (piece.start - rule.source.start);
+				result.insert_non_identity(piece.clone(), destination);
+			}
+		}
+
+		result
+	}
+
+	fn insert_non_identity(&mut self, source: Range<Idx>, destination: Idx) {
+		if source.start != destination {
+			self.insert(source, destination);
+		}
+	}
+
+	/// Splits the range on the boundaries of the rules, and pairs each piece with the value its
+	/// start maps to.
+	fn split(&self, range: Range<Idx>) -> Vec<(Range<Idx>, Idx)> {
+		let mut result = Vec::new();
+
+		let first = self.rules.partition_point(|r| r.source.end <= range.start);
+		let mut position = range.start;
+
+		for rule in self.rules[first..]
+			.iter()
+			.take_while(|r| r.source.start < range.end)
+		{
+			if position < rule.source.start {
+				result.push((position..rule.source.start, position));
+				position = rule.source.start;
+			}
+
+			let end = rule.source.end.min(range.end);
+			let destination = rule.destination + (position - rule.source.start);
+			result.push((position..end, destination));
+			position = end;
+		}
+
+		if position < range.end {
+			result.push((position..range.end, position));
+		}
+
+		result
+	}
+}
+
+impl<Idx> Default for RangeMap<Idx> {
+	fn default() -> Self {
+		Self { rules: Vec::new() }
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::RangeMap;
+
+	#[test]
+	fn test_compose_overlapping_images() {
+		let mut first = RangeMap::new();
+		first.insert(0..10, 15);
+		first.insert(10..20, 0);
+
+		let mut second = RangeMap::new();
+		second.insert(5..20, 105);
+
+		let composed = first.compose(&second);
+		for item in 0..30 {
+			assert_eq!(composed.get(item), second.get(first.get(item)), "{item}");
+		}
+	}
+
+	#[test]
+	#[should_panic]
+	fn test_insert_overlapping() {
+		let mut map = RangeMap::new();
+		map.insert(0..10, 100);
+		map.insert(5..15, 200);
+	}
+}
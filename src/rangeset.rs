@@ -0,0 +1,265 @@
+use std::{
+	iter::Sum,
+	ops::{Range, Sub},
+};
+
+/// A set of values, stored as sorted ranges. Overlapping and touching ranges are merged on
+/// insertion, so the ranges are always disjoint and never empty.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeSet<Idx> {
+	ranges: Vec<Range<Idx>>,
+}
+
+impl<Idx> RangeSet<Idx>
+where
+	Idx: Ord + Copy,
+{
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// ```rust
+	/// # use aoc2023::rangeset::RangeSet;
+	/// let mut set = RangeSet::new();
+	///
+	/// set.insert(0..10);
+	/// set.insert(20..30);
+	/// assert!(set.iter().eq(&[0..10, 20..30]));
+	///
+	/// // Overlapping ranges are merged
+	/// set.insert(5..15);
+	/// assert!(set.iter().eq(&[0..15, 20..30]));
+	///
+	/// // Touching ranges are merged as well
+	/// set.insert(15..20);
+	/// assert!(set.iter().eq(&[0..30]));
+	///
+	/// // Empty ranges are ignored
+	/// set.insert(40..40);
+	/// assert!(set.iter().eq(&[0..30]));
+	/// ```
+	pub fn insert(&mut self, range: Range<Idx>) {
+		if range.is_empty() {
+			return;
+		}
+
+		let first = self.ranges.partition_point(|r| r.end < range.start);
+		let last = self.ranges.partition_point(|r| r.start <= range.end);
+
+		let merged = if first < last {
+			let start = range.start.min(self.ranges[first].start);
+			let end = range.end.max(self.ranges[last - 1].end);
+			start..end
+		} else {
+			range
+		};
+
+		self.ranges.splice(first..last, [merged]);
+	}
+
+	/// ```rust
+	/// # use aoc2023::rangeset::RangeSet;
+	/// let mut set = RangeSet::from_iter([0..10, 20..30]);
+	///
+	/// // Splitting a range in two
+	/// set.remove(3..6);
+	/// assert!(set.iter().eq(&[0..3, 6..10, 20..30]));
+	///
+	/// // Removing over multiple ranges
+	/// set.remove(8..25);
+	/// assert!(set.iter().eq(&[0..3, 6..8, 25..30]));
+	///
+	/// // Removing a range that isn't in the set
+	/// set.remove(40..50);
+	/// assert!(set.iter().eq(&[0..3, 6..8, 25..30]));
+	/// ```
+	pub fn remove(&mut self, range: Range<Idx>) {
+		if range.is_empty() {
+			return;
+		}
+
+		let first = self.ranges.partition_point(|r| r.end <= range.start);
+		let last = self.ranges.partition_point(|r| r.start < range.end);
+
+		if first >= last {
+			return;
+		}
+
+		let before = self.ranges[first].start..range.start;
+		let after = range.end..self.ranges[last - 1].end;
+		let remaining = [before, after].into_iter().filter(|r| !r.is_empty());
+
+		self.ranges.splice(first..last, remaining);
+	}
+
+	/// ```rust
+	/// # use aoc2023::rangeset::RangeSet;
+	/// let set = RangeSet::from_iter([0..10, 20..30]);
+	///
+	/// assert!(set.contains(0));
+	/// assert!(set.contains(25));
+	/// assert!(!set.contains(10));
+	/// assert!(!set.contains(15));
+	/// ```
+	pub fn contains(&self, item: Idx) -> bool {
+		let index = self.ranges.partition_point(|r| r.end <= item);
+		self.ranges.get(index).is_some_and(|r| r.contains(&item))
+	}
+
+	/// ```rust
+	/// # use aoc2023::rangeset::RangeSet;
+	/// let fst = RangeSet::from_iter([0..10, 20..30]);
+	/// let snd = RangeSet::from_iter([5..25, 40..50]);
+	///
+	/// assert!(fst.union(&snd).iter().eq(&[0..30, 40..50]));
+	/// ```
+	pub fn union(&self, other: &Self) -> Self {
+		let mut result = self.clone();
+		result.extend(other.iter().cloned());
+		result
+	}
+
+	/// ```rust
+	/// # use aoc2023::rangeset::RangeSet;
+	/// let fst = RangeSet::from_iter([0..10, 20..30]);
+	/// let snd = RangeSet::from_iter([5..25, 28..50]);
+	///
+	/// assert!(fst.intersection(&snd).iter().eq(&[5..10, 20..25, 28..30]));
+	/// ```
+	pub fn intersection(&self, other: &Self) -> Self {
+		let mut ranges = Vec::new();
+		let (mut i, mut j) = (0, 0);
+
+		while let (Some(r1), Some(r2)) = (self.ranges.get(i), other.ranges.get(j)) {
+			let start = r1.start.max(r2.start);
+			let end = r1.end.min(r2.end);
+			if start < end {
+				ranges.push(start..end);
+			}
+
+			if r1.end <= r2.end {
+				i += 1;
+			} else {
+				j += 1;
+			}
+		}
+
+		// Intersections of disjoint, sorted ranges are disjoint and sorted themselves.
+		Self { ranges }
+	}
+
+	/// ```rust
+	/// # use aoc2023::rangeset::RangeSet;
+	/// let fst = RangeSet::from_iter([0..10, 20..30]);
+	/// let snd = RangeSet::from_iter([5..25]);
+	///
+	/// assert!(fst.difference(&snd).iter().eq(&[0..5, 25..30]));
+	/// assert!(snd.difference(&fst).iter().eq(&[10..20]));
+	/// ```
+	pub fn difference(&self, other: &Self) -> Self {
+		let mut result = self.clone();
+		for range in other.iter() {
+			result.remove(range.clone());
+		}
+		result
+	}
+
+	/// The total amount of values in the set.
+	///
+	/// ```rust
+	/// # use aoc2023::rangeset::RangeSet;
+	/// let mut set = RangeSet::new();
+	/// assert_eq!(set.len(), 0);
+	///
+	/// set.insert(0..10);
+	/// set.insert(5..15);
+	/// set.insert(20..30);
+	/// assert_eq!(set.len(), 25);
+	/// ```
+	pub fn len(&self) -> Idx
+	where
+		Idx: Sub<Output = Idx> + Sum,
+	{
+		self.ranges.iter().map(|r| r.end - r.start).sum()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.ranges.is_empty()
+	}
+
+	/// ```rust
+	/// # use aoc2023::rangeset::RangeSet;
+	/// let mut set = RangeSet::new();
+	/// assert_eq!(set.min(), None);
+	///
+	/// set.insert(20..30);
+	/// set.insert(10..15);
+	/// assert_eq!(set.min(), Some(10));
+	/// ```
+	pub fn min(&self) -> Option<Idx> {
+		self.ranges.first().map(|r| r.start)
+	}
+
+	/// The disjoint ranges of the set, in ascending order.
+	pub fn iter(&self) -> impl Iterator<Item = &Range<Idx>> + '_ {
+		self.ranges.iter()
+	}
+}
+
+impl<Idx> Default for RangeSet<Idx> {
+	fn default() -> Self {
+		Self { ranges: Vec::new() }
+	}
+}
+
+impl<Idx> Extend<Range<Idx>> for RangeSet<Idx>
+where
+	Idx: Ord + Copy,
+{
+	fn extend<T: IntoIterator<Item = Range<Idx>>>(&mut self, iter: T) {
+		for range in iter {
+			self.insert(range);
+		}
+	}
+}
+
+impl<Idx> FromIterator<Range<Idx>> for RangeSet<Idx>
+where
+	Idx: Ord + Copy,
+{
+	fn from_iter<T: IntoIterator<Item = Range<Idx>>>(iter: T) -> Self {
+		let mut set = Self::default();
+		set.extend(iter);
+		set
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::RangeSet;
+
+	#[test]
+	fn test_insert_bridging_multiple_ranges() {
+		let mut set = RangeSet::from_iter([0..2, 4..6, 8..10, 20..30]);
+		set.insert(1..9);
+
+		assert!(set.iter().eq(&[0..10, 20..30]));
+	}
+
+	#[test]
+	fn test_remove_everything() {
+		let mut set = RangeSet::from_iter([0..2, 4..6, 8..10]);
+		set.remove(0..10);
+
+		assert!(set.is_empty());
+		assert_eq!(set.min(), None);
+	}
+
+	#[test]
+	fn test_signed_indices() {
+		let set = RangeSet::from_iter([-10..-5, -7..3]);
+
+		assert_eq!(set.iter().collect::<Vec<_>>(), vec![&(-10..3)]);
+		assert_eq!(set.len(), 13);
+	}
+}